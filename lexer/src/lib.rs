@@ -1,5 +1,5 @@
 use derive_more::Display;
-use logos::Logos;
+use logos::{Filter, Lexer, Logos};
 use token_macro_derive::TokenInfo;
 
 #[derive(TokenInfo, Logos, Debug, Display, PartialEq, Eq, Clone, Copy, Hash)]
@@ -15,7 +15,7 @@ pub enum Token {
     #[display(fmt = "`}}`")]
     ClosingBrace,
 
-    #[category(Symbol, FirstTokenOfExpression)]
+    #[category(Symbol, FirstTokenOfExpression, FirstTokenOfPattern)]
     #[token("(")]
     #[display(fmt = "`(`")]
     OpeningParen,
@@ -45,6 +45,16 @@ pub enum Token {
     #[display(fmt = "`.`")]
     Dot,
 
+    #[category(Symbol, FirstTokenOfExpression, FirstTokenOfPattern)]
+    #[token("..")]
+    #[display(fmt = "`..`")]
+    DotDot,
+
+    #[category(Symbol, FirstTokenOfExpression, FirstTokenOfPattern)]
+    #[token("..=")]
+    #[display(fmt = "`..=`")]
+    DotDotEquals,
+
     #[category(Symbol)]
     #[token(":")]
     #[display(fmt = "`:`")]
@@ -80,6 +90,21 @@ pub enum Token {
     #[display(fmt = "`!`")]
     Exclamation,
 
+    #[category(Symbol, FirstTokenOfExpression)]
+    #[token("|")]
+    #[display(fmt = "`|`")]
+    Pipe,
+
+    #[category(BinaryOperator)]
+    #[token("|>")]
+    #[display(fmt = "`|>`")]
+    PipeGreater,
+
+    #[category(Symbol)]
+    #[token("@")]
+    #[display(fmt = "`@`")]
+    At,
+
     #[category(Symbol)]
     #[token("=")]
     #[display(fmt = "`=`")]
@@ -110,7 +135,13 @@ pub enum Token {
     #[display(fmt = "`+`")]
     Plus,
 
-    #[category(BinaryOperator, UnaryOperator, SumOperator, FirstTokenOfExpression)]
+    #[category(
+        BinaryOperator,
+        UnaryOperator,
+        SumOperator,
+        FirstTokenOfExpression,
+        FirstTokenOfPattern
+    )]
     #[token("-")]
     #[display(fmt = "`-`")]
     Minus,
@@ -170,6 +201,16 @@ pub enum Token {
     #[display(fmt = "the keyword `not`")]
     Not,
 
+    #[category(Keyword)]
+    #[token("is")]
+    #[display(fmt = "the keyword `is`")]
+    Is,
+
+    #[category(Keyword)]
+    #[token("as")]
+    #[display(fmt = "the keyword `as`")]
+    As,
+
     #[category(Keyword, ItemKeyword)]
     #[token("struct")]
     #[display(fmt = "the keyword `struct`")]
@@ -180,32 +221,87 @@ pub enum Token {
     #[display(fmt = "the keyword `fn`")]
     Function,
 
-    #[category(Keyword)]
+    #[category(Keyword, ItemKeyword)]
     #[token("let")]
     #[display(fmt = "the keyword `let`")]
     Let,
 
-    #[category(Keyword)]
+    #[category(Keyword, ItemKeyword)]
     #[token("module")]
     #[display(fmt = "the keyword `module`")]
     Module,
 
-    #[category(FirstTokenOfExpression)]
+    #[category(Keyword, FirstTokenOfExpression)]
+    #[token("if")]
+    #[display(fmt = "the keyword `if`")]
+    If,
+
+    #[category(Keyword)]
+    #[token("else")]
+    #[display(fmt = "the keyword `else`")]
+    Else,
+
+    #[category(Keyword, FirstTokenOfExpression)]
+    #[token("match")]
+    #[display(fmt = "the keyword `match`")]
+    Match,
+
+    #[category(Keyword, FirstTokenOfExpression)]
+    #[token("while")]
+    #[display(fmt = "the keyword `while`")]
+    While,
+
+    #[category(Keyword, FirstTokenOfExpression)]
+    #[token("loop")]
+    #[display(fmt = "the keyword `loop`")]
+    Loop,
+
+    #[category(Keyword, FirstTokenOfExpression)]
+    #[token("for")]
+    #[display(fmt = "the keyword `for`")]
+    For,
+
+    #[category(Keyword)]
+    #[token("in")]
+    #[display(fmt = "the keyword `in`")]
+    In,
+
+    #[category(Keyword, FirstTokenOfExpression)]
+    #[token("break")]
+    #[display(fmt = "the keyword `break`")]
+    Break,
+
+    #[category(Keyword, FirstTokenOfExpression)]
+    #[token("continue")]
+    #[display(fmt = "the keyword `continue`")]
+    Continue,
+
+    #[category(Keyword, FirstTokenOfExpression)]
+    #[token("return")]
+    #[display(fmt = "the keyword `return`")]
+    Return,
+
+    #[category(FirstTokenOfExpression, FirstTokenOfPattern)]
     #[regex("[_a-zA-Z]+[_a-zA-Z0-9]*", priority = 2)]
     #[display(fmt = "an identifier")]
     Identifier,
 
-    #[category(Literal, FirstTokenOfExpression)]
-    #[regex(r#""([^"\\]*(\\.[^"\\]*)*)""#)]
+    #[category(FirstTokenOfExpression)]
+    #[regex("'[_a-zA-Z]+[_a-zA-Z0-9]*")]
+    #[display(fmt = "a label")]
+    Label,
+
+    #[category(Literal, FirstTokenOfExpression, FirstTokenOfPattern)]
+    #[token("\"", lex_string)]
     #[display(fmt = "a string literal")]
     String,
 
-    #[category(Literal, FirstTokenOfExpression)]
+    #[category(Literal, FirstTokenOfExpression, FirstTokenOfPattern)]
     #[regex(r"[_0-9]+")]
     #[display(fmt = "an integer literal")]
     Integer,
 
-    #[category(Literal, FirstTokenOfExpression)]
+    #[category(Literal, FirstTokenOfExpression, FirstTokenOfPattern)]
     #[regex(r"[_0-9]+\.[0-9_]+")]
     #[display(fmt = "a float literal")]
     Float,
@@ -214,12 +310,144 @@ pub enum Token {
     #[display(fmt = "a comment")]
     LineComment,
 
+    // Block comments are skipped like any other comment, so this is only ever emitted for a block comment that's
+    // missing its closing `*/`. In that case, it only spans the opening `/*`.
+    #[token("/*", lex_block_comment)]
+    #[display(fmt = "an unterminated block comment")]
+    UnterminatedBlockComment,
+
+    // Like in Rust, four or more slashes make a plain comment, so that `////` can be used for separators.
+    #[regex(r"///([^/\n][^\n]*)?")]
+    #[display(fmt = "a doc comment")]
+    OuterDocComment,
+
+    #[regex(r"//![^\n]*")]
+    #[display(fmt = "an inner doc comment")]
+    InnerDocComment,
+
     #[error]
     #[regex(r"[ \t\n\f\s]+", logos::skip)]
     #[display(fmt = "<error>")]
     Error,
 }
 
+// Strings can contain interpolated expressions, like `"hello {name}"`, and those expressions can contain strings of
+// their own. A regex can't keep track of that nesting, so strings are lexed by hand instead. The interpolations are
+// parsed later, by the parser.
+fn lex_string(lexer: &mut Lexer<Token>) -> bool {
+    let remainder = lexer.remainder();
+    let length = match string_length(remainder, false) {
+        StringEnd::Closed(length) => Some(length),
+        // An interpolation that's never closed would otherwise swallow the rest of the input, so the string ends at its
+        // first unescaped quote instead. The parser reports the unclosed `{` when it parses the interpolation.
+        StringEnd::UnclosedInterpolation => plain_string_length(remainder),
+        StringEnd::Unterminated => None,
+    };
+
+    match length {
+        Some(length) => {
+            lexer.bump(length);
+            true
+        }
+        None => {
+            // An unterminated string swallows the rest of the input, rather than having its contents lexed as code.
+            lexer.bump(remainder.len());
+            false
+        }
+    }
+}
+
+enum StringEnd {
+    /// The string is closed, and has this length.
+    Closed(usize),
+    /// The input ends before the string does.
+    Unterminated,
+    /// An interpolation reaches a newline or the end of the input before its closing `}`.
+    UnclosedInterpolation,
+}
+
+/// Find the length of a string literal, not including its opening quote but including its closing one. `{{` and `}}`
+/// are escapes for literal braces, and anything between a lone `{` and its matching `}` is an interpolation, which
+/// can't span multiple lines.
+fn string_length(source: &str, in_interpolation: bool) -> StringEnd {
+    let bytes = source.as_bytes();
+    let mut depth = 0usize;
+    let mut index = 0;
+
+    while index < bytes.len() {
+        match (bytes[index], bytes.get(index + 1)) {
+            (b'\\', _) => index += 1,
+            (b'"', _) if depth == 0 => return StringEnd::Closed(index + 1),
+            (b'"', _) => match string_length(&source[index + 1..], true) {
+                StringEnd::Closed(length) => index += length,
+                _ => return StringEnd::UnclosedInterpolation,
+            },
+            (b'\n', _) if depth > 0 || in_interpolation => return StringEnd::UnclosedInterpolation,
+            (b'{', Some(b'{')) | (b'}', Some(b'}')) if depth == 0 => index += 1,
+            (b'{', _) => depth += 1,
+            (b'}', _) if depth > 0 => depth -= 1,
+            _ => (),
+        }
+
+        index += 1;
+    }
+
+    if depth > 0 || in_interpolation {
+        StringEnd::UnclosedInterpolation
+    } else {
+        StringEnd::Unterminated
+    }
+}
+
+/// Find the length of a string literal like [`string_length`], but without treating any braces as interpolations.
+fn plain_string_length(source: &str) -> Option<usize> {
+    let bytes = source.as_bytes();
+    let mut index = 0;
+
+    while index < bytes.len() {
+        match bytes[index] {
+            b'\\' => index += 1,
+            b'"' => return Some(index + 1),
+            _ => (),
+        }
+
+        index += 1;
+    }
+
+    None
+}
+
+// Block comments nest, so that commenting out code that already contains a block comment works as expected. Like with
+// strings, a regex can't keep track of that, so we find the end of the comment by hand.
+fn lex_block_comment(lexer: &mut Lexer<Token>) -> Filter<()> {
+    let bytes = lexer.remainder().as_bytes();
+    let mut depth = 1usize;
+    let mut index = 0;
+
+    while index < bytes.len() {
+        match (bytes[index], bytes.get(index + 1)) {
+            (b'/', Some(b'*')) => {
+                depth += 1;
+                index += 2;
+            }
+            (b'*', Some(b'/')) => {
+                depth -= 1;
+                index += 2;
+
+                if depth == 0 {
+                    lexer.bump(index);
+                    return Filter::Skip;
+                }
+            }
+            _ => index += 1,
+        }
+    }
+
+    // The token only spans the opening `/*`, so that an error can point at where the comment starts. The rest of the
+    // input is left for the parser to skip.
+    Filter::Emit(())
+}
+
 // You may be wondering something along the lines of "what the hell how is this macro here and where does it come from"
 // if you've been reading the rest of the source. The answer is that the `category_derive` macro emits a `macro_rules!`
 // macro named `token_category`. It looks a bit similar to the below:
@@ -253,4 +481,95 @@ mod tests {
     fn test_lexer() {
         let _lexer = Token::lexer("source");
     }
+
+    #[test]
+    fn test_interpolated_strings() {
+        let tokens: Vec<_> = Token::lexer(r#""a {f("b", "{c}")} d" "{{}}" "e\"""#).collect();
+        assert_eq!(tokens, vec![Token::String, Token::String, Token::String]);
+    }
+
+    #[test]
+    fn test_unterminated_strings() {
+        let tokens: Vec<_> = Token::lexer(r#"x "a {"b"} c"#).collect();
+        assert_eq!(tokens, vec![Token::Identifier, Token::Error]);
+    }
+
+    #[test]
+    fn test_unclosed_interpolations() {
+        // The string ends at its first quote, instead of swallowing everything after the unclosed `{`.
+        let mut lexer = Token::lexer(r#"fn f() { "a {b" }"#);
+        let tokens: Vec<_> = lexer.by_ref().take(5).collect();
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Function,
+                Token::Identifier,
+                Token::OpeningParen,
+                Token::ClosingParen,
+                Token::OpeningBrace
+            ]
+        );
+        assert_eq!(lexer.next(), Some(Token::String));
+        assert_eq!(lexer.slice(), r#""a {b""#);
+        assert_eq!(lexer.next(), Some(Token::ClosingBrace));
+
+        // Interpolations can't span multiple lines, even when the string is closed on a later one.
+        let mut lexer = Token::lexer("\"a {b\nc\" d }\"");
+        assert_eq!(lexer.next(), Some(Token::String));
+        assert_eq!(lexer.slice(), "\"a {b\nc\"");
+
+        // Strings without any interpolations can still span multiple lines.
+        let tokens: Vec<_> = Token::lexer("\"a\nb\"").collect();
+        assert_eq!(tokens, vec![Token::String]);
+    }
+
+    #[test]
+    fn test_nested_block_comments() {
+        let tokens: Vec<_> = Token::lexer("a /* b /* c */ d */ e").collect();
+        assert_eq!(tokens, vec![Token::Identifier, Token::Identifier]);
+    }
+
+    #[test]
+    fn test_unterminated_block_comments() {
+        let mut lexer = Token::lexer("a /* b /* c */");
+        assert_eq!(lexer.next(), Some(Token::Identifier));
+        assert_eq!(lexer.next(), Some(Token::UnterminatedBlockComment));
+        assert_eq!(lexer.span(), 2..4);
+    }
+
+    #[test]
+    fn test_doc_comments() {
+        let tokens: Vec<_> = Token::lexer("//! Module.\n/// Item.\n// Comment.\nfn").collect();
+        assert_eq!(
+            tokens,
+            vec![
+                Token::InnerDocComment,
+                Token::OuterDocComment,
+                Token::Function,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_separator_comments_are_not_doc_comments() {
+        let tokens: Vec<_> = Token::lexer("////////\n//// Comment.\n///\nfn").collect();
+        assert_eq!(tokens, vec![Token::OuterDocComment, Token::Function]);
+    }
+
+    #[test]
+    fn test_ranges_are_not_floats() {
+        let tokens: Vec<_> = Token::lexer("1..2 1..=2 1.5").collect();
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Integer,
+                Token::DotDot,
+                Token::Integer,
+                Token::Integer,
+                Token::DotDotEquals,
+                Token::Integer,
+                Token::Float,
+            ]
+        );
+    }
 }
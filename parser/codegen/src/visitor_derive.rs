@@ -1,7 +1,7 @@
 use proc_macro::TokenStream;
-use proc_macro2::Span;
+use proc_macro2::{Span, TokenStream as TokenStream2};
 use quote::quote;
-use syn::{Ident, Item, Token, TypePath};
+use syn::{Fields, Ident, Item, ItemEnum, ItemStruct, Token, TypePath};
 
 syn::custom_keyword!(base);
 syn::custom_keyword!(node);
@@ -63,11 +63,36 @@ fn impl_base(item: &Item) -> TokenStream {
 }
 
 fn impl_visited(item: &Item, type_path: TypePath) -> TokenStream {
-    let item = match item {
-        Item::Struct(struct_) => struct_,
-        _ => panic!("only structs may be visited in this way"),
+    let (type_name, type_generics, visit_body) = match item {
+        Item::Struct(struct_) => (
+            &struct_.ident,
+            &struct_.generics,
+            impl_visited_struct(struct_),
+        ),
+        Item::Enum(enum_) => (&enum_.ident, &enum_.generics, impl_visited_enum(enum_)),
+        _ => panic!("only structs or enums may be visited in this way"),
     };
 
+    let type_generic_bounds = type_generics.params.iter();
+
+    let tokens = quote! {
+        impl#type_generics Visitor<#type_path> for #type_name#type_generics
+        where
+            #(#type_generic_bounds: Visitor<#type_path>),*
+        {
+            fn visit<F>(&self, op: &mut F)
+            where
+                F: FnMut(&#type_path),
+            {
+                #visit_body
+            }
+        }
+    };
+
+    tokens.into()
+}
+
+fn impl_visited_struct(item: &ItemStruct) -> TokenStream2 {
     let visit_calls = item.fields.iter().enumerate().filter_map(|(index, field)| {
         field
             .attrs
@@ -81,23 +106,33 @@ fn impl_visited(item: &Item, type_path: TypePath) -> TokenStream {
             })
     });
 
-    let type_name = &item.ident;
-    let type_generics = &item.generics;
-    let type_generic_bounds = item.generics.params.iter();
+    quote! {
+        #(self.#visit_calls.visit(op);)*
+    }
+}
 
-    let tokens = quote! {
-        impl#type_generics Visitor<#type_path> for #type_name#type_generics
-        where
-            #(#type_generic_bounds: Visitor<#type_path>),*
-        {
-            fn visit<F>(&self, op: &mut F)
-            where
-                F: FnMut(&#type_path),
-            {
-                #(self.#visit_calls.visit(op);)*
+fn impl_visited_enum(item: &ItemEnum) -> TokenStream2 {
+    // Only variants marked with `#[visit]` are visited. Every such variant must have exactly one tuple field, which is
+    // visited in turn.
+    let visit_arms = item
+        .variants
+        .iter()
+        .filter(|variant| variant.attrs.iter().any(|attr| attr.path.is_ident("visit")))
+        .map(|variant| {
+            let variant_ident = &variant.ident;
+            match &variant.fields {
+                Fields::Unnamed(fields) if fields.unnamed.len() == 1 => quote! {
+                    Self::#variant_ident(field___) => field___.visit(op),
+                },
+                _ => panic!("visited enum variants must have exactly one tuple field"),
             }
-        }
-    };
+        });
 
-    tokens.into()
+    quote! {
+        #[allow(unreachable_patterns)]
+        match self {
+            #(#visit_arms)*
+            _ => {}
+        }
+    }
 }
@@ -15,6 +15,12 @@ pub struct OperatorInfo<T> {
     span: Span,
 }
 
+impl<T> OperatorInfo<T> {
+    pub fn operator(&self) -> &T {
+        &self.operator
+    }
+}
+
 #[derive(Debug, Display, Clone)]
 pub enum UnaryOperator {
     #[display(fmt = "not")]
@@ -53,6 +59,20 @@ pub enum BinaryOperator {
     Or,
 }
 
+#[derive(Debug, Display, Clone)]
+pub enum AssignOperator {
+    #[display(fmt = "=")]
+    Assign,
+    #[display(fmt = "+=")]
+    AddAssign,
+    #[display(fmt = "-=")]
+    SubtractAssign,
+    #[display(fmt = "*=")]
+    MultiplyAssign,
+    #[display(fmt = "/=")]
+    DivideAssign,
+}
+
 #[derive(Debug, Clone, Visitor, Spanned)]
 #[visit(node = Expression)]
 #[span(self.span)]
@@ -75,6 +95,101 @@ pub struct BinaryExpression {
     pub right: Box<Expression>,
 }
 
+/// A cast to another type, like `x as Float`.
+#[derive(Debug, Clone, Visitor, Spanned)]
+#[visit(node = Expression)]
+#[span(self.span)]
+pub struct CastExpression {
+    pub span: Span,
+    #[visit]
+    pub expression: Box<Expression>,
+    pub target: TypeExpr,
+}
+
+/// An expression annotated with the type it's expected to have, like `xs: List[Int]`.
+#[derive(Debug, Clone, Visitor, Spanned)]
+#[visit(node = Expression)]
+#[span(self.span)]
+pub struct AscriptionExpression {
+    pub span: Span,
+    #[visit]
+    pub expression: Box<Expression>,
+    pub annotation: TypeExpr,
+}
+
+/// A range, like `a..b` or `a..=b`. Either end may be left out, as in `..b`, `a..` or even `..`, but an inclusive
+/// range always needs an end.
+#[derive(Debug, Clone, Visitor, Spanned)]
+#[visit(node = Expression)]
+#[span(self.span)]
+pub struct RangeExpression {
+    pub span: Span,
+    #[visit]
+    pub start: Option<Box<Expression>>,
+    #[visit]
+    pub end: Option<Box<Expression>>,
+    pub inclusive: bool,
+}
+
+/// A pipeline, like `x |> f(y)`, which passes `input` as the first argument to `function`. This is kept around as-is for
+/// tooling, but means exactly the same thing as [PipelineExpression::desugar].
+#[derive(Debug, Clone, Visitor, Spanned)]
+#[visit(node = Expression)]
+#[span(self.span)]
+pub struct PipelineExpression {
+    pub span: Span,
+    #[visit]
+    pub input: Box<Expression>,
+    #[visit]
+    pub function: Box<Expression>,
+}
+
+impl PipelineExpression {
+    /// Turn this pipeline into the call it stands for. `x |> f(y)` becomes `f(x, y)`, and `x |> f` becomes `f(x)`. A
+    /// chain of pipelines is desugared all the way, so `x |> f |> g` becomes `g(f(x))`.
+    pub fn desugar(&self) -> Expression {
+        let input = match &*self.input {
+            Expression::Pipeline(pipeline) => pipeline.desugar(),
+            input => input.clone(),
+        };
+        let input = Argument::Positional(input);
+
+        match &*self.function {
+            Expression::Call(call) => {
+                let mut arguments = vec![input];
+                arguments.extend(call.arguments.iter().cloned());
+
+                Call {
+                    span: self.span,
+                    operand: call.operand.clone(),
+                    arguments,
+                }
+                .into()
+            }
+            function => Call {
+                span: self.span,
+                operand: Box::new(function.clone()),
+                arguments: vec![input],
+            }
+            .into(),
+        }
+    }
+}
+
+/// An assignment, like `x = 1`, or a compound assignment, like `x += 1`. Assignments are right associative, so
+/// `a = b = c` assigns `c` to `b` before assigning the result to `a`.
+#[derive(Debug, Clone, Visitor, Spanned)]
+#[visit(node = Expression)]
+#[span(self.span)]
+pub struct AssignExpression {
+    pub span: Span,
+    pub operator: OperatorInfo<AssignOperator>,
+    #[visit]
+    pub target: Box<Expression>,
+    #[visit]
+    pub value: Box<Expression>,
+}
+
 #[derive(Debug, Clone, Visitor, Spanned)]
 #[visit(node = Expression)]
 #[span(self.span)]
@@ -91,11 +206,155 @@ pub struct IsExpression {
 pub struct BlockExpression {
     pub span: Span,
     #[visit]
-    pub statements: Vec<Expression>,
+    pub statements: Vec<Statement>,
     #[visit]
     pub tail: Option<Box<Expression>>,
 }
 
+#[derive(Debug, Clone, Visitor, Spanned)]
+#[visit(node = Expression)]
+#[span(self.span)]
+pub struct IfExpression {
+    pub span: Span,
+    #[visit]
+    pub condition: Box<Expression>,
+    #[visit]
+    pub then_branch: Box<Expression>,
+    #[visit]
+    pub else_branch: Option<Box<Expression>>,
+}
+
+impl IfExpression {
+    /// Get the patterns tested by this expression's condition. These are the patterns of any `is` expressions in the
+    /// condition, as long as they're only joined by `and`. Any bindings they introduce are in scope in the `then` branch,
+    /// but not in the `else` branch.
+    pub fn condition_patterns(&self) -> Vec<&Pattern> {
+        fn collect<'a>(expression: &'a Expression, patterns: &mut Vec<&'a Pattern>) {
+            match expression {
+                Expression::Is(is) => patterns.push(&is.pattern),
+                Expression::Binary(binary)
+                    if matches!(binary.operator.operator, BinaryOperator::And) =>
+                {
+                    collect(&binary.left, patterns);
+                    collect(&binary.right, patterns);
+                }
+                _ => (),
+            }
+        }
+
+        let mut patterns = Vec::new();
+        collect(&self.condition, &mut patterns);
+        patterns
+    }
+}
+
+#[derive(Debug, Clone, Visitor, Spanned)]
+#[visit(node = Expression)]
+#[span(self.span)]
+pub struct MatchArm {
+    pub span: Span,
+    pub pattern: Pattern,
+    #[visit]
+    pub guard: Option<Box<Expression>>,
+    #[visit]
+    pub body: Box<Expression>,
+}
+
+#[derive(Debug, Clone, Visitor, Spanned)]
+#[visit(node = Expression)]
+#[span(self.span)]
+pub struct MatchExpression {
+    pub span: Span,
+    #[visit]
+    pub scrutinee: Box<Expression>,
+    #[visit]
+    pub arms: Vec<MatchArm>,
+}
+
+#[derive(Debug, Clone, Visitor, Spanned)]
+#[visit(node = Expression)]
+#[span(self.span)]
+pub struct WhileExpression {
+    pub span: Span,
+    pub label: Option<Label>,
+    #[visit]
+    pub condition: Box<Expression>,
+    #[visit]
+    pub body: Box<Expression>,
+}
+
+#[derive(Debug, Clone, Visitor, Spanned)]
+#[visit(node = Expression)]
+#[span(self.span)]
+pub struct LoopExpression {
+    pub span: Span,
+    pub label: Option<Label>,
+    #[visit]
+    pub body: Box<Expression>,
+}
+
+#[derive(Debug, Clone, Visitor, Spanned)]
+#[visit(node = Expression)]
+#[span(self.span)]
+pub struct ForExpression {
+    pub span: Span,
+    pub label: Option<Label>,
+    pub pattern: Pattern,
+    #[visit]
+    pub iterable: Box<Expression>,
+    #[visit]
+    pub body: Box<Expression>,
+}
+
+/// A `break`, optionally out of a labelled loop, and optionally with a value, as in `break 'outer value`.
+#[derive(Debug, Clone, Visitor, Spanned)]
+#[visit(node = Expression)]
+#[span(self.span)]
+pub struct BreakExpression {
+    pub span: Span,
+    pub label: Option<Label>,
+    #[visit]
+    pub value: Option<Box<Expression>>,
+}
+
+#[derive(Debug, Clone, Spanned)]
+#[span(self.span)]
+pub struct ContinueExpression {
+    pub span: Span,
+    pub label: Option<Label>,
+}
+
+#[derive(Debug, Clone, Visitor, Spanned)]
+#[visit(node = Expression)]
+#[span(self.span)]
+pub struct ReturnExpression {
+    pub span: Span,
+    #[visit]
+    pub value: Option<Box<Expression>>,
+}
+
+/// A single field in a struct literal. `value` is `None` when the field is written in shorthand, as in `Point { x }`.
+#[derive(Debug, Clone, Visitor, Spanned)]
+#[visit(node = Expression)]
+#[span(self.span)]
+pub struct FieldInitializer {
+    pub span: Span,
+    pub name: Identifer,
+    #[visit]
+    pub value: Option<Box<Expression>>,
+}
+
+#[derive(Debug, Clone, Visitor, Spanned)]
+#[visit(node = Expression)]
+#[span(self.span)]
+pub struct StructExpression {
+    pub span: Span,
+    #[visit]
+    pub name: Box<Expression>,
+    #[visit]
+    pub fields: Vec<FieldInitializer>,
+}
+
 macro_rules! literal_impl {
     ($($vis:vis $name:ident,)+) => { literal_impl! { $($vis $name),* } };
     ($($vis:vis $name:ident),*) => {
@@ -116,7 +375,16 @@ literal_impl! {
     pub IntegerLiteral,
     pub FloatLiteral,
     pub StringLiteral,
+    pub UnitLiteral,
     pub Identifer,
+    pub Label,
+}
+
+impl UnitLiteral {
+    // Unlike the other literals, `()` is made up of two tokens, so it can't be converted from a single one.
+    pub(crate) fn new(span: Span) -> Self {
+        UnitLiteral(span)
+    }
 }
 
 #[derive(Debug, Clone, From, Spanned)]
@@ -124,33 +392,115 @@ pub enum LiteralExpression {
     Integer(IntegerLiteral),
     Float(FloatLiteral),
     String(StringLiteral),
+    Unit(UnitLiteral),
+}
+
+/// A part of an [InterpolatedString].
+#[derive(Debug, Clone, Visitor, Spanned)]
+#[visit(node = Expression)]
+pub enum StringPart {
+    /// Literal text, exactly as it's written in the source. Escapes like `\n` and `{{` haven't been processed yet.
+    Literal(Span),
+    #[visit]
+    Expression(Expression),
+}
+
+/// A string literal with expressions interpolated into it, like `"hello {name}"`. Strings without any interpolations
+/// are plain [StringLiteral]s instead.
+#[derive(Debug, Clone, Visitor, Spanned)]
+#[visit(node = Expression)]
+#[span(self.span)]
+pub struct InterpolatedString {
+    pub span: Span,
+    #[visit]
+    pub parts: Vec<StringPart>,
+}
+
+/// A tuple, like `(a, b)`. A tuple with a single element needs a trailing comma, as in `(a,)`, since `(a)` is just
+/// `a` in parentheses.
+#[derive(Debug, Clone, Visitor, Spanned)]
+#[visit(node = Expression)]
+#[span(self.span)]
+pub struct TupleExpression {
+    pub span: Span,
+    #[visit]
+    pub elements: Vec<Expression>,
+}
+
+/// An anonymous function, like `|x| x + 1` or `|x: Int| -> Int { x + 1 }`. Lambda parameters can't have defaults.
+#[derive(Debug, Clone, Visitor, Spanned)]
+#[visit(node = Expression)]
+#[span(self.span)]
+pub struct LambdaExpression {
+    pub span: Span,
+    #[visit]
+    pub parameters: Vec<Parameter>,
+    pub return_type: Option<TypeExpr>,
+    #[visit]
+    pub body: Box<Expression>,
+}
+
+/// An array literal that lists its elements, like `[a, b, c]`.
+#[derive(Debug, Clone, Visitor, Spanned)]
+#[visit(node = Expression)]
+#[span(self.span)]
+pub struct ArrayExpression {
+    pub span: Span,
+    #[visit]
+    pub elements: Vec<Expression>,
+}
+
+/// An array literal that repeats a single value, like `[value; count]`.
+#[derive(Debug, Clone, Visitor, Spanned)]
+#[visit(node = Expression)]
+#[span(self.span)]
+pub struct ArrayRepeatExpression {
+    pub span: Span,
+    #[visit]
+    pub value: Box<Expression>,
+    #[visit]
+    pub count: Box<Expression>,
+}
+
+#[derive(Debug, Clone, Visitor, Spanned)]
+#[visit(node = Expression)]
+#[span(self.span)]
+pub struct IndexExpression {
+    pub span: Span,
+    #[visit]
+    pub operand: Box<Expression>,
+    #[visit]
+    pub index: Box<Expression>,
 }
 
 #[derive(Debug, Clone, Visitor, Spanned)]
 #[visit(node = Expression)]
 #[span(self.span)]
 pub struct Call {
-    span: Span,
+    pub span: Span,
+    #[visit]
+    pub operand: Box<Expression>,
     #[visit]
-    operand: Box<Expression>,
-    // TODO: visit for enums
-    arguments: Vec<Argument>,
+    pub arguments: Vec<Argument>,
 }
 
 #[derive(Debug, Clone, Visitor, Spanned)]
 #[visit(node = Expression)]
 #[span(self.span)]
 pub struct NamedArgument {
-    span: Span,
-    name: Identifer,
+    pub span: Span,
+    pub name: Identifer,
     #[visit]
-    expression: Box<Expression>,
+    pub expression: Box<Expression>,
 }
 
-#[derive(Debug, Clone, Spanned)]
+#[derive(Debug, Clone, Visitor, Spanned)]
+#[visit(node = Expression)]
 pub enum Argument {
+    #[visit]
     Named(NamedArgument),
-    Positional(Expression)
+    #[visit]
+    Positional(Expression),
 }
 
 #[derive(Debug, Clone, From, Visitor, Spanned)]
@@ -158,14 +508,252 @@ pub enum Argument {
 pub enum Expression {
     Unary(UnaryExpression),
     Binary(BinaryExpression),
+    Assign(AssignExpression),
+    Pipeline(PipelineExpression),
+    Range(RangeExpression),
+    Cast(CastExpression),
+    Ascription(AscriptionExpression),
     Is(IsExpression),
     Block(BlockExpression),
+    If(IfExpression),
+    Match(MatchExpression),
+    While(WhileExpression),
+    Loop(LoopExpression),
+    For(ForExpression),
+    Break(BreakExpression),
+    Continue(ContinueExpression),
+    Return(ReturnExpression),
     Literal(LiteralExpression),
+    InterpolatedString(InterpolatedString),
     Identifier(Identifer),
+    Path(PathExpression),
     Call(Call),
+    Struct(StructExpression),
+    Tuple(TupleExpression),
+    Lambda(LambdaExpression),
+    Array(ArrayExpression),
+    ArrayRepeat(ArrayRepeatExpression),
+    Index(IndexExpression),
+    Error(Span),
+}
+
+#[derive(Debug, Clone, Visitor, Spanned)]
+#[visit(node = Expression)]
+#[span(self.span)]
+pub struct LetStatement {
+    pub span: Span,
+    pub attributes: Vec<Attribute>,
+    pub pattern: Pattern,
+    pub annotation: Option<TypeExpr>,
+    #[visit]
+    pub value: Box<Expression>,
+}
+
+#[derive(Debug, Clone, Visitor, Spanned)]
+#[visit(node = Expression)]
+#[span(self.expression.span())]
+pub struct ExpressionStatement {
+    pub attributes: Vec<Attribute>,
+    #[visit]
+    pub expression: Expression,
+}
+
+#[derive(Debug, Clone, From, Visitor, Spanned)]
+#[visit(node = Expression)]
+pub enum Statement {
+    #[visit]
+    Let(LetStatement),
+    #[visit]
+    Expression(ExpressionStatement),
+}
+
+/// A path made up only of `::`-separated names, like `inline` or `std::test`.
+#[derive(Debug, Clone, Spanned)]
+#[span(self.span)]
+pub struct Path {
+    pub span: Span,
+    pub segments: Vec<Identifer>,
+}
+
+/// A group of token trees surrounded by matching delimiters.
+#[derive(Debug, Clone, Spanned)]
+#[span(self.span)]
+pub struct DelimitedTree {
+    pub span: Span,
+    pub open: SpannedToken,
+    pub trees: Vec<TokenTree>,
+    pub close: SpannedToken,
+}
+
+/// Either a single token, or a group of tokens surrounded by delimiters. Attribute arguments are kept as token trees,
+/// since what they mean depends entirely on the attribute.
+#[derive(Debug, Clone, Spanned)]
+pub enum TokenTree {
+    Token(SpannedToken),
+    Delimited(DelimitedTree),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttributeStyle {
+    /// `#[...]` or `///`, which applies to whatever follows it.
+    Outer,
+    /// `#![...]` or `//!`, which applies to the module it's in.
+    Inner,
+}
+
+#[derive(Debug, Clone)]
+pub enum AttributeKind {
+    /// An attribute written out in full, like `#[derive(Debug)]`.
+    Normal {
+        path: Path,
+        arguments: Vec<TokenTree>,
+    },
+    /// A doc comment, like `/// Some docs.` or `//! Some docs.`. The span covers the text of the comment, after the
+    /// `///` or `//!`.
+    Doc(Span),
+}
+
+#[derive(Debug, Clone, Spanned)]
+#[span(self.span)]
+pub struct Attribute {
+    pub span: Span,
+    pub style: AttributeStyle,
+    pub kind: AttributeKind,
+}
+
+#[derive(Debug, Clone, Visitor, Spanned)]
+#[visit(node = Expression)]
+#[span(self.span)]
+pub struct Parameter {
+    pub span: Span,
+    pub pattern: Pattern,
+    pub annotation: Option<TypeExpr>,
+    #[visit]
+    pub default: Option<Box<Expression>>,
+}
+
+impl Parameter {
+    /// Get the name of this parameter, if it has one. Only parameters with a name can be bound to by named arguments.
+    pub fn name(&self) -> Option<&WordPattern> {
+        match &self.pattern {
+            Pattern::Word(word) => Some(word),
+            _ => None,
+        }
+    }
+}
+
+/// A generic parameter of a function or struct, like `T: Show + Eq = Int`. A variadic parameter, like `..Ts`, stands
+/// for any number of types, and must come last.
+#[derive(Debug, Clone, Spanned)]
+#[span(self.span)]
+pub struct GenericParameter {
+    pub span: Span,
+    pub variadic: bool,
+    pub name: Identifer,
+    pub bounds: Vec<TypeExpr>,
+    pub default: Option<TypeExpr>,
+}
+
+/// The signature of a function - everything from the `fn` keyword up until the body.
+#[derive(Debug, Clone, Visitor, Spanned)]
+#[visit(node = Expression)]
+#[span(self.span)]
+pub struct FunctionSignature {
+    pub span: Span,
+    pub name: Identifer,
+    pub generics: Vec<GenericParameter>,
+    #[visit]
+    pub parameters: Vec<Parameter>,
+    pub return_type: Option<TypeExpr>,
+}
+
+#[derive(Debug, Clone, Visitor, Spanned)]
+#[visit(node = Expression)]
+#[span(self.span)]
+pub struct FunctionItem {
+    pub span: Span,
+    pub attributes: Vec<Attribute>,
+    #[visit]
+    pub signature: FunctionSignature,
+    #[visit]
+    pub body: Box<Expression>,
+}
+
+#[derive(Debug, Clone, Spanned)]
+#[span(self.span)]
+pub struct NamedField {
+    pub span: Span,
+    pub attributes: Vec<Attribute>,
+    pub name: Identifer,
+    pub annotation: TypeExpr,
+}
+
+#[derive(Debug, Clone, Spanned)]
+#[span(self.span)]
+pub struct TupleField {
+    pub span: Span,
+    pub attributes: Vec<Attribute>,
+    pub annotation: TypeExpr,
+}
+
+#[derive(Debug, Clone)]
+pub enum StructFields {
+    /// `struct Point { x: Int, y: Int }`
+    Named(Vec<NamedField>),
+    /// `struct Point(Int, Int);`
+    Tuple(Vec<TupleField>),
+    /// `struct Point;`
+    Unit,
+}
+
+#[derive(Debug, Clone, Spanned)]
+#[span(self.span)]
+pub struct StructItem {
+    pub span: Span,
+    pub attributes: Vec<Attribute>,
+    pub name: Identifer,
+    pub generics: Vec<GenericParameter>,
+    pub fields: StructFields,
+}
+
+/// A `module` item. `body` is `None` when this only declares a module, as in `module name;`. The contents of a module
+/// like that live in another file - see [crate::loader] for how they're found.
+#[derive(Debug, Clone, Visitor, Spanned)]
+#[visit(node = Expression)]
+#[span(self.span)]
+pub struct ModuleItem {
+    pub span: Span,
+    pub attributes: Vec<Attribute>,
+    pub name: Identifer,
+    #[visit]
+    pub body: Option<Module>,
+}
+
+#[derive(Debug, Clone, From, Visitor, Spanned)]
+#[visit(node = Expression)]
+pub enum Item {
+    #[visit]
+    Let(LetStatement),
+    #[visit]
+    Function(FunctionItem),
+    Struct(StructItem),
+    #[visit]
+    Module(ModuleItem),
     Error(Span),
 }
 
+/// A module, comprised of the items within it. The root module of a file spans the entire file.
+#[derive(Debug, Clone, Visitor, Spanned)]
+#[visit(node = Expression)]
+#[span(self.span)]
+pub struct Module {
+    pub span: Span,
+    /// Inner attributes, which apply to the module itself.
+    pub attributes: Vec<Attribute>,
+    #[visit]
+    pub items: Vec<Item>,
+}
+
 // Here we unfortunately run into a small spot where we're context-sensitive. I will not solve this with a lexer hack
 // since I am not evil. Instead, this will be caught in a later compilation stage, where we start to understand
 // semantics of a source file a bit more.
@@ -186,10 +774,186 @@ pub struct WordPattern {
     pub span: Span,
 }
 
-#[derive(Debug, Clone, Visitor)]
+/// A pattern that matches a constructor and its fields, like `Maybe::Some(value)`.
+#[derive(Debug, Clone, Spanned)]
+#[span(self.span)]
+pub struct ConstructorPattern {
+    pub span: Span,
+    pub path: Path,
+    pub fields: Vec<Pattern>,
+}
+
+/// A single field within a [StructPattern]. A field written without a pattern, like the `x` in `Point { x }`, binds
+/// to its own name - in that case, `pattern` is a [WordPattern] with the same span as `name`.
+#[derive(Debug, Clone, Spanned)]
+#[span(self.span)]
+pub struct FieldPattern {
+    pub span: Span,
+    pub name: Identifer,
+    pub pattern: Pattern,
+}
+
+/// A pattern that destructures a struct by its field names, like `Point { x, y: 0, .. }`.
+#[derive(Debug, Clone, Spanned)]
+#[span(self.span)]
+pub struct StructPattern {
+    pub span: Span,
+    pub path: Path,
+    pub fields: Vec<FieldPattern>,
+    /// The span of the `..` that ignores the remaining fields, if there is one.
+    pub rest: Option<Span>,
+}
+
+#[derive(Debug, Clone, Spanned)]
+#[span(self.span)]
+pub struct TuplePattern {
+    pub span: Span,
+    pub elements: Vec<Pattern>,
+}
+
+/// A pattern that matches if any of its alternatives do, like `1 | 2 | 3`.
+#[derive(Debug, Clone, Spanned)]
+#[span(self.span)]
+pub struct OrPattern {
+    pub span: Span,
+    pub alternatives: Vec<Pattern>,
+}
+
+/// A pattern that binds a name to the value matched by another pattern, like `small @ 0..10`.
+#[derive(Debug, Clone, Spanned)]
+#[span(self.span)]
+pub struct BindingPattern {
+    pub span: Span,
+    pub name: WordPattern,
+    pub pattern: Box<Pattern>,
+}
+
+/// A literal in a pattern. Unlike in an expression, a number can be negated here, like in `-1`.
+#[derive(Debug, Clone, Spanned)]
+#[span(self.span)]
+pub struct LiteralPattern {
+    pub span: Span,
+    /// Whether the literal is preceded by a `-`. This is only ever true for integer and float literals.
+    pub negative: bool,
+    pub literal: LiteralExpression,
+}
+
+/// A pattern that matches a range of literals, like `0..10` or `-1.0..=1.0`. Either end may be left out, but not both.
+#[derive(Debug, Clone, Spanned)]
+#[span(self.span)]
+pub struct RangePattern {
+    pub span: Span,
+    pub start: Option<LiteralPattern>,
+    pub end: Option<LiteralPattern>,
+    pub inclusive: bool,
+}
+
+#[derive(Debug, Clone, From, Visitor, Spanned)]
 #[visit(base)]
 pub enum Pattern {
     Word(WordPattern),
+    /// The `_` pattern, which matches anything without binding it.
+    #[from(ignore)]
+    Wildcard(Span),
+    Literal(LiteralPattern),
+    Path(Path),
+    Constructor(ConstructorPattern),
+    Struct(StructPattern),
+    Tuple(TuplePattern),
+    Or(OrPattern),
+    Binding(BindingPattern),
+    Range(RangePattern),
+    /// The `..` pattern, which matches any number of remaining elements in a tuple or constructor.
+    #[from(ignore)]
+    Rest(Span),
+    #[from(ignore)]
+    Error(Span),
+}
+
+impl Pattern {
+    /// Get every word pattern within this pattern. Each of these *may* introduce a binding - see [WordPattern] for why
+    /// we can't be sure yet.
+    pub fn bindings(&self) -> Vec<&WordPattern> {
+        match self {
+            Pattern::Word(word) => vec![word],
+            Pattern::Constructor(constructor) => constructor
+                .fields
+                .iter()
+                .flat_map(Pattern::bindings)
+                .collect(),
+            Pattern::Struct(structure) => structure
+                .fields
+                .iter()
+                .flat_map(|field| field.pattern.bindings())
+                .collect(),
+            Pattern::Tuple(tuple) => tuple.elements.iter().flat_map(Pattern::bindings).collect(),
+            // Every alternative has to bind the same names, so the first is as good as any.
+            Pattern::Or(or) => or
+                .alternatives
+                .first()
+                .map_or_else(Vec::new, Pattern::bindings),
+            Pattern::Binding(binding) => {
+                let mut bindings = vec![&binding.name];
+                bindings.extend(binding.pattern.bindings());
+                bindings
+            }
+            Pattern::Wildcard(_)
+            | Pattern::Literal(_)
+            | Pattern::Path(_)
+            | Pattern::Range(_)
+            | Pattern::Rest(_)
+            | Pattern::Error(_) => Vec::new(),
+        }
+    }
+}
+
+/// A generic type applied to some arguments, like `List[Int]`.
+#[derive(Debug, Clone, Spanned)]
+#[span(self.span)]
+pub struct GenericType {
+    pub span: Span,
+    pub base: Path,
+    pub arguments: Vec<TypeExpr>,
+}
+
+/// A tuple type, like `(Int, String)`. The unit type `()` is a tuple with no elements.
+#[derive(Debug, Clone, Spanned)]
+#[span(self.span)]
+pub struct TupleType {
+    pub span: Span,
+    pub elements: Vec<TypeExpr>,
+}
+
+/// The type of a function, like `fn(Int) -> Int`.
+#[derive(Debug, Clone, Spanned)]
+#[span(self.span)]
+pub struct FunctionType {
+    pub span: Span,
+    pub parameters: Vec<TypeExpr>,
+    pub return_type: Option<Box<TypeExpr>>,
+}
+
+/// An array type, like `[Int]`, optionally with a length, like `[Int; 4]`.
+#[derive(Debug, Clone, Spanned)]
+#[span(self.span)]
+pub struct ArrayType {
+    pub span: Span,
+    pub element: Box<TypeExpr>,
+    pub length: Option<Box<Expression>>,
+}
+
+#[derive(Debug, Clone, From, Visitor, Spanned)]
+#[visit(base)]
+pub enum TypeExpr {
+    Path(Path),
+    Generic(GenericType),
+    Tuple(TupleType),
+    Function(FunctionType),
+    Array(ArrayType),
+    /// A type that should be inferred, written as `_`.
+    #[from(ignore)]
+    Hole(Span),
+    #[from(ignore)]
     Error(Span),
 }
 
@@ -197,7 +961,7 @@ pub enum Pattern {
 #[visit(base)]
 pub enum AstNode {}
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum PathAccess {
     // `::` access
     Scope,
@@ -205,6 +969,70 @@ pub enum PathAccess {
     Member,
 }
 
+/// A single `::name` or `.name` segment of a path.
+#[derive(Debug, Clone, Spanned)]
+#[span(self.span)]
+pub struct PathSegment {
+    pub span: Span,
+    pub access: PathAccess,
+    pub name: Identifer,
+}
+
+/// A chain of path segments following an expression, like `a::b::c` or `x.method`.
+#[derive(Debug, Clone, Visitor, Spanned)]
+#[visit(node = Expression)]
+#[span(self.span)]
+pub struct PathExpression {
+    pub span: Span,
+    #[visit]
+    pub root: Box<Expression>,
+    pub segments: Vec<PathSegment>,
+}
+
+impl Expression {
+    /// Whether this expression ends with a block. Expressions like this don't need a trailing semicolon when they're
+    /// used as statements.
+    pub fn is_block_like(&self) -> bool {
+        matches!(
+            self,
+            Expression::Block(_)
+                | Expression::If(_)
+                | Expression::Match(_)
+                | Expression::While(_)
+                | Expression::Loop(_)
+                | Expression::For(_)
+        )
+    }
+}
+
+impl Expression {
+    /// Whether this expression is a path that could name a type, like `Point` or `shapes::Point`. Paths that contain
+    /// member access don't count.
+    pub fn is_type_path(&self) -> bool {
+        match self {
+            Expression::Identifier(_) => true,
+            Expression::Path(path) => {
+                matches!(*path.root, Expression::Identifier(_))
+                    && path
+                        .segments
+                        .iter()
+                        .all(|segment| segment.access == PathAccess::Scope)
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Expression {
+    /// Whether this expression can be assigned to, like `x`, `point.x` or `values[0]`.
+    pub fn is_assignable(&self) -> bool {
+        matches!(
+            self,
+            Expression::Identifier(_) | Expression::Path(_) | Expression::Index(_)
+        )
+    }
+}
+
 impl ErrorVariant for Expression {
     fn error(span: Span) -> Self {
         Expression::Error(span)
@@ -215,6 +1043,55 @@ impl ErrorVariant for Expression {
     }
 }
 
+impl ErrorVariant for Pattern {
+    fn error(span: Span) -> Self {
+        Pattern::Error(span)
+    }
+
+    fn is_error(&self) -> bool {
+        matches!(self, Pattern::Error(_))
+    }
+}
+
+impl From<Expression> for Statement {
+    fn from(expression: Expression) -> Self {
+        Statement::Expression(ExpressionStatement {
+            attributes: Vec::new(),
+            expression,
+        })
+    }
+}
+
+impl ErrorVariant for Statement {
+    fn error(span: Span) -> Self {
+        Expression::Error(span).into()
+    }
+
+    fn is_error(&self) -> bool {
+        matches!(self, Statement::Expression(statement) if statement.expression.is_error())
+    }
+}
+
+impl ErrorVariant for Item {
+    fn error(span: Span) -> Self {
+        Item::Error(span)
+    }
+
+    fn is_error(&self) -> bool {
+        matches!(self, Item::Error(_))
+    }
+}
+
+impl ErrorVariant for TypeExpr {
+    fn error(span: Span) -> Self {
+        TypeExpr::Error(span)
+    }
+
+    fn is_error(&self) -> bool {
+        matches!(self, TypeExpr::Error(_))
+    }
+}
+
 #[derive(Debug)]
 pub struct TryFromTokenError {
     token: Token,
@@ -286,6 +1163,39 @@ where
     }
 }
 
+impl TryFrom<Token> for AssignOperator {
+    type Error = TryFromTokenError;
+
+    fn try_from(value: Token) -> Result<Self, Self::Error> {
+        match value {
+            Token::Equals => Ok(AssignOperator::Assign),
+            Token::PlusEquals => Ok(AssignOperator::AddAssign),
+            Token::MinusEquals => Ok(AssignOperator::SubtractAssign),
+            Token::AsteriskEquals => Ok(AssignOperator::MultiplyAssign),
+            Token::SlashEquals => Ok(AssignOperator::DivideAssign),
+            token => Err(TryFromTokenError {
+                token,
+                type_name: "AssignOperator",
+            }),
+        }
+    }
+}
+
+impl TryFrom<Token> for PathAccess {
+    type Error = TryFromTokenError;
+
+    fn try_from(value: Token) -> Result<Self, Self::Error> {
+        match value {
+            Token::ColonColon => Ok(PathAccess::Scope),
+            Token::Dot => Ok(PathAccess::Member),
+            token => Err(TryFromTokenError {
+                token,
+                type_name: "PathAccess",
+            }),
+        }
+    }
+}
+
 impl TryFrom<SpannedToken> for LiteralExpression {
     type Error = TryFromTokenError;
 
@@ -302,6 +1212,20 @@ impl TryFrom<SpannedToken> for LiteralExpression {
     }
 }
 
+impl TryFrom<SpannedToken> for Label {
+    type Error = TryFromTokenError;
+
+    fn try_from(value: SpannedToken) -> Result<Self, Self::Error> {
+        match value.kind() {
+            Token::Label => Ok(Label(value.span())),
+            token => Err(TryFromTokenError {
+                token,
+                type_name: "Label",
+            }),
+        }
+    }
+}
+
 impl TryFrom<SpannedToken> for Identifer {
     type Error = TryFromTokenError;
 
@@ -115,6 +115,7 @@ impl From<Option<Token>> for Unexpected {
 fn format_terms(terms: &[DiagnosticTerm]) -> Cow<'static, str> {
     match terms {
         [] => "no tokens".into(),
+        [single] => single.to_string().into(),
         [first, second] => format!("{} or {}", first, second).into(),
         [head @ .., tail] => {
             let mut string = "either ".to_owned();
@@ -156,6 +157,17 @@ pub trait ErrorVariant {
     fn is_error(&self) -> bool;
 }
 
+// Parsers that produce an `Option` have no way to represent an error, so they produce nothing at all instead.
+impl<T> ErrorVariant for Option<T> {
+    fn error(_: Span) -> Self {
+        None
+    }
+
+    fn is_error(&self) -> bool {
+        self.is_none()
+    }
+}
+
 pub trait IntoDiagnostic {
     fn into_diagnostic(self, parser: &Parser) -> DiagnosticTerm;
 }
@@ -1,5 +1,6 @@
 pub mod ast;
 pub mod error;
+pub mod loader;
 pub mod parser;
 mod parser_impl;
 pub mod pattern;
@@ -11,6 +12,7 @@ pub use ast::*;
 pub use error::{
     Diagnostic, DiagnosticTerm, Error, ErrorKind, IntoDiagnostic, LabelExt, SpannedTokenExt,
 };
+pub use loader::{ChildModule, LoadedModule};
 pub use parser::Parser;
 pub use pattern::Pattern;
 pub use span::Span;
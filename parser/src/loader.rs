@@ -0,0 +1,339 @@
+use crate::{
+    error::{Diagnostic, Error, ErrorKind},
+    span::{Span, Spanned},
+    Item, Module, ModuleItem, Parser,
+};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// The extension used for Dusk source files.
+pub const SOURCE_EXTENSION: &str = "dusk";
+
+/// A module that has been loaded from a file, along with every module it declares that lives in another file.
+///
+/// A declaration like `module name;` in the root module refers to the file `name.dusk`, next to the root module's file.
+/// Every other module gets a directory named after it for the modules it declares, whether it's inline or has a file
+/// of its own. So within `module inner { ... }` or in `inner.dusk`, `module name;` refers to `inner/name.dusk`. Errors that come from loading one of these files (like the file not existing) are
+/// reported in the file that contains the declaration, at the span of the declaration.
+pub struct LoadedModule {
+    pub path: PathBuf,
+    pub source: String,
+    pub module: Module,
+    /// The span of the `module name;` item that this module was loaded from, in the parent module's source. This is
+    /// `None` for the root module.
+    pub declaration: Option<Span>,
+    pub errors: Vec<Error>,
+    /// The modules declared directly within this one, in the order they're declared.
+    pub children: Vec<ChildModule>,
+}
+
+/// A module declared within a [LoadedModule], which mirrors the [ModuleItem] that declares it.
+pub enum ChildModule {
+    /// An inline module, like `module name { ... }`, along with the modules declared within it.
+    Inline {
+        /// The span of the [ModuleItem], in the source of the file that contains it.
+        declaration: Span,
+        children: Vec<ChildModule>,
+    },
+    /// A module declared as `module name;`, which was loaded from a file of its own.
+    File(LoadedModule),
+}
+
+impl LoadedModule {
+    /// Load the module at `path`, along with every module that it (transitively) declares.
+    ///
+    /// This only fails if the root module itself can't be read. Any problems with other modules are reported as errors
+    /// in the module that declares them.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<LoadedModule> {
+        let path = path.as_ref().to_owned();
+        let source = fs::read_to_string(&path)?;
+        let mut loading = vec![path.canonicalize()?];
+        let directory = path.parent().map(Path::to_owned).unwrap_or_default();
+
+        Ok(Self::load_source(
+            path,
+            source,
+            None,
+            &directory,
+            &mut loading,
+        ))
+    }
+
+    /// Parse a module's source, and load the modules it declares relative to `directory`.
+    fn load_source(
+        path: PathBuf,
+        source: String,
+        declaration: Option<Span>,
+        directory: &Path,
+        loading: &mut Vec<PathBuf>,
+    ) -> LoadedModule {
+        let mut parser = Parser::new(&source);
+        let module = parser.parse_module();
+        let mut errors = parser.errors;
+
+        let children = load_children(&module, &source, directory, &mut errors, loading);
+
+        LoadedModule {
+            path,
+            source,
+            module,
+            declaration,
+            errors,
+            children,
+        }
+    }
+}
+
+/// Load every module declared directly within `module`, resolving declarations relative to `directory`. Inline modules
+/// are walked recursively, with their own subdirectory.
+fn load_children(
+    module: &Module,
+    source: &str,
+    directory: &Path,
+    errors: &mut Vec<Error>,
+    loading: &mut Vec<PathBuf>,
+) -> Vec<ChildModule> {
+    let mut children = Vec::new();
+
+    for item in module.items.iter() {
+        let item = match item {
+            Item::Module(item) => item,
+            _ => continue,
+        };
+
+        match &item.body {
+            Some(body) => {
+                let directory = directory.join(&source[item.name.span()]);
+                children.push(ChildModule::Inline {
+                    declaration: item.span,
+                    children: load_children(body, source, &directory, errors, loading),
+                });
+            }
+            None => {
+                if let Some(child) = load_declaration(item, source, directory, errors, loading) {
+                    children.push(ChildModule::File(child));
+                }
+            }
+        }
+    }
+
+    children
+}
+
+/// Load the file for a `module name;` declaration, which is `name.dusk` in `directory`. Its own declarations are
+/// relative to the `name` subdirectory. If it can't be loaded, an error is reported at the declaration.
+fn load_declaration(
+    item: &ModuleItem,
+    source: &str,
+    directory: &Path,
+    errors: &mut Vec<Error>,
+    loading: &mut Vec<PathBuf>,
+) -> Option<LoadedModule> {
+    let span = item.span;
+    let name = &source[item.name.span()];
+    let child_path = directory.join(name).with_extension(SOURCE_EXTENSION);
+
+    let canonical = match child_path.canonicalize() {
+        Ok(canonical) => canonical,
+        Err(error) => {
+            let message = format!(
+                "couldn't find the file `{}` for the module `{}`: {}",
+                child_path.display(),
+                name,
+                error
+            );
+
+            errors.push(message_error(span, message));
+            return None;
+        }
+    };
+
+    if loading.contains(&canonical) {
+        let message = format!(
+            "the module `{}` (at `{}`) is already being loaded, so loading it here would cause a cycle",
+            name,
+            child_path.display()
+        );
+
+        errors.push(message_error(span, message));
+        return None;
+    }
+
+    let child_source = match fs::read_to_string(&child_path) {
+        Ok(child_source) => child_source,
+        Err(error) => {
+            let message = format!(
+                "couldn't read the file `{}` for the module `{}`: {}",
+                child_path.display(),
+                name,
+                error
+            );
+
+            errors.push(message_error(span, message));
+            return None;
+        }
+    };
+
+    loading.push(canonical);
+    let child_directory = directory.join(name);
+    let child = LoadedModule::load_source(
+        child_path,
+        child_source,
+        Some(span),
+        &child_directory,
+        loading,
+    );
+    loading.pop();
+
+    Some(child)
+}
+
+fn message_error(location: Span, message: String) -> Error {
+    Error::new(
+        location,
+        ErrorKind::Diagnostic(Diagnostic::Message {
+            message: message.into(),
+        }),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A temporary directory, which is removed once the test using it is done (even if it fails).
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn join(&self, path: impl AsRef<Path>) -> PathBuf {
+            self.0.join(path)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    /// Write each `(path, source)` pair into a fresh temporary directory, returning the directory. The directory is
+    /// unique to this run of the tests, so that separate runs can't interfere with each other.
+    fn write_files(name: &str, files: &[(&str, &str)]) -> TempDir {
+        let directory =
+            std::env::temp_dir().join(format!("dusk-loader-{}-{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&directory);
+
+        for (path, source) in files {
+            let path = directory.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, source).unwrap();
+        }
+
+        TempDir(directory)
+    }
+
+    fn file(child: &ChildModule) -> &LoadedModule {
+        match child {
+            ChildModule::File(module) => module,
+            ChildModule::Inline { .. } => panic!("expected a file module"),
+        }
+    }
+
+    fn inline(child: &ChildModule) -> &[ChildModule] {
+        match child {
+            ChildModule::Inline { children, .. } => children,
+            ChildModule::File(_) => panic!("expected an inline module"),
+        }
+    }
+
+    #[test]
+    fn test_nested_declarations() {
+        let directory = write_files(
+            "nested",
+            &[
+                (
+                    "main.dusk",
+                    "module a { module util; } module b { module util; } module top;",
+                ),
+                ("a/util.dusk", "fn a() {}"),
+                ("b/util.dusk", "fn b() {}"),
+                ("top.dusk", ""),
+            ],
+        );
+
+        let root = LoadedModule::load(directory.join("main.dusk")).unwrap();
+        assert!(root.errors.is_empty());
+        assert_eq!(root.children.len(), 3);
+
+        let a = file(&inline(&root.children[0])[0]);
+        assert_eq!(a.path, directory.join("a/util.dusk"));
+        let b = file(&inline(&root.children[1])[0]);
+        assert_eq!(b.path, directory.join("b/util.dusk"));
+        assert_eq!(file(&root.children[2]).path, directory.join("top.dusk"));
+    }
+
+    #[test]
+    fn test_file_declarations() {
+        // A module with a file of its own resolves its declarations like an inline module with the same name would.
+        let directory = write_files(
+            "file",
+            &[
+                ("main.dusk", "module a; module b { module c; }"),
+                ("a.dusk", "module c;"),
+                ("a/c.dusk", "fn a() {}"),
+                ("b/c.dusk", "fn b() {}"),
+                ("c.dusk", "fn wrong() {}"),
+            ],
+        );
+
+        let root = LoadedModule::load(directory.join("main.dusk")).unwrap();
+        let a = file(&root.children[0]);
+        assert!(a.errors.is_empty());
+        assert_eq!(file(&a.children[0]).path, directory.join("a/c.dusk"));
+
+        let b = inline(&root.children[1]);
+        assert_eq!(file(&b[0]).path, directory.join("b/c.dusk"));
+    }
+
+    #[test]
+    fn test_missing_files() {
+        let directory = write_files("missing", &[("main.dusk", "module inner { module gone; }")]);
+
+        let root = LoadedModule::load(directory.join("main.dusk")).unwrap();
+        assert_eq!(root.errors.len(), 1);
+        assert_eq!(&root.source.as_str()[root.errors[0].span()], "module gone;");
+        assert!(inline(&root.children[0]).is_empty());
+    }
+
+    // Every module's declarations are resolved in a directory of its own, so a cycle can only come from a symlink.
+    #[cfg(unix)]
+    #[test]
+    fn test_cycles() {
+        let directory = write_files(
+            "cycle",
+            &[
+                ("main.dusk", "module other;"),
+                ("other.dusk", "module main;"),
+            ],
+        );
+        fs::create_dir(directory.join("other")).unwrap();
+        std::os::unix::fs::symlink(
+            directory.join("main.dusk"),
+            directory.join("other/main.dusk"),
+        )
+        .unwrap();
+
+        let root = LoadedModule::load(directory.join("main.dusk")).unwrap();
+        assert!(root.errors.is_empty());
+
+        let other = file(&root.children[0]);
+        assert_eq!(other.errors.len(), 1);
+        assert_eq!(
+            &other.source.as_str()[other.errors[0].span()],
+            "module main;"
+        );
+        assert!(other.children.is_empty());
+    }
+}
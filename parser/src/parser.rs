@@ -4,13 +4,46 @@ use crate::{
 };
 
 use super::*;
-use lexer::Token;
+use lexer::{token_category, Token};
 use logos::{Lexer, Logos};
 
 #[inline]
-fn _next_impl(lexer: &mut Lexer<'_, Token>) -> Result<SpannedToken, Error> {
-    lexer
-        .next()
+fn _next_impl(
+    lexer: &mut Lexer<'_, Token>,
+    errors: &mut Vec<Error>,
+) -> Result<SpannedToken, Error> {
+    let mut token = lexer.next();
+
+    // An unterminated block comment or string runs to the end of the input. Block comments are only given the span of
+    // their `/*` by the lexer, and strings are reported at their opening quote. Everything after that is skipped here,
+    // rather than being parsed as code. Lookahead sees it too, so it's only reported the first time it's seen.
+    let unterminated = match token {
+        Some(Token::UnterminatedBlockComment) => Some("unterminated block comment"),
+        Some(Token::Error) if lexer.slice().starts_with('"') => Some("unterminated string"),
+        _ => None,
+    };
+
+    if let Some(message) = unterminated {
+        let start = lexer.span().start;
+        let location = match token {
+            Some(Token::Error) => Span::new(start, start + 1),
+            _ => lexer.span().into(),
+        };
+
+        if !errors.iter().any(|error| error.location == location) {
+            errors.push(Error::new(
+                location,
+                ErrorKind::Diagnostic(Diagnostic::Message {
+                    message: message.into(),
+                }),
+            ));
+        }
+
+        lexer.bump(lexer.remainder().len());
+        token = lexer.next();
+    }
+
+    token
         .map(|token| SpannedToken(lexer.span().into(), token))
         .ok_or_else(|| Error::new(lexer.span().into(), ErrorKind::Simple(Unexpected::Eof)))
 }
@@ -23,6 +56,9 @@ pub struct Parser<'source> {
     pub(crate) lexer: Lexer<'source, Token>,
     pub(crate) errors: Vec<Error>,
     pub(crate) unclosed_delimiters: Vec<SpannedToken>,
+    pub(crate) allow_struct_literals: bool,
+    /// The labels of the loops we're currently inside of, from outermost to innermost. Unlabelled loops are `None`.
+    pub(crate) loop_labels: Vec<Option<SpannedToken>>,
 }
 
 impl<'source> Parser<'source> {
@@ -31,6 +67,8 @@ impl<'source> Parser<'source> {
             lexer: Token::lexer(input),
             errors: Vec::new(),
             unclosed_delimiters: Vec::new(),
+            allow_struct_literals: true,
+            loop_labels: Vec::new(),
         }
     }
 
@@ -42,12 +80,22 @@ impl<'source> Parser<'source> {
         self.lexer.source()
     }
 
-    pub fn peek(&self) -> Result<SpannedToken, Error> {
-        _next_impl(&mut self.lexer.clone())
+    pub fn peek(&mut self) -> Result<SpannedToken, Error> {
+        _next_impl(&mut self.lexer.clone(), &mut self.errors)
+    }
+
+    /// Peek the token `n` tokens past the next token in the stream. `peek_nth(0)` is equivalent to `peek()`.
+    pub fn peek_nth(&mut self, n: usize) -> Result<SpannedToken, Error> {
+        let mut lexer = self.lexer.clone();
+        for _ in 0..n {
+            _next_impl(&mut lexer, &mut self.errors)?;
+        }
+
+        _next_impl(&mut lexer, &mut self.errors)
     }
 
     pub fn next(&mut self) -> Result<SpannedToken, Error> {
-        _next_impl(&mut self.lexer)
+        _next_impl(&mut self.lexer, &mut self.errors)
     }
 
     /// Peek the next token in the stream, to see if it matches a pattern. This will not progress the parser - it is
@@ -69,16 +117,48 @@ impl<'source> Parser<'source> {
             .or_else(self.recover_with_token_deletion(token, expected))
     }
 
+    /// Consume the next token in the stream if it matches a pattern. If it doesn't, but it matches `recover`, an error
+    /// is reported and the token is left in the stream, as if the expected token had been inserted before it.
+    /// Otherwise, this behaves like [Parser::expect_matches].
     pub fn expect_matches_or_recover_with(
         &mut self,
         expected: impl Pattern + IntoDiagnostic + Copy,
         recover: impl Pattern + IntoDiagnostic,
     ) -> Result<SpannedToken, Error> {
-        let token = self.next()?;
-        expected
-            .match_pattern(self, token)
-            .or_else(self.recover_with_token_insertion(token, expected, recover))
-            .or_else(self.recover_with_token_deletion(token, expected))
+        let token = self.peek()?;
+        match expected.match_pattern(self, token) {
+            Ok(token) => {
+                let _ = self.next();
+                Ok(token)
+            }
+            Err(error) => self.recover_with_token_insertion(token, expected, recover)(error)
+                .or_else(|error| {
+                    let _ = self.next();
+                    self.recover_with_token_deletion(token, expected)(error)
+                }),
+        }
+    }
+
+    /// Skip tokens until the next token in the stream matches a pattern. Tokens inside of nested delimiters are skipped
+    /// over entirely, and a closing delimiter that doesn't match the pattern stops the skipping, as it belongs to
+    /// whatever we're nested in. The matching token is not consumed.
+    pub fn skip_until(&mut self, expected: impl Pattern) {
+        let mut depth = 0usize;
+
+        while let Ok(token) = self.peek() {
+            if depth == 0 && expected.match_pattern(self, token).is_ok() {
+                break;
+            }
+
+            match token.kind() {
+                Token::OpeningBrace | Token::OpeningParen | Token::OpeningBracket => depth += 1,
+                token_category![ClosingBracket] if depth == 0 => break,
+                token_category![ClosingBracket] => depth -= 1,
+                _ => (),
+            }
+
+            let _ = self.next();
+        }
     }
 
     pub fn cursor(&self) -> Cursor {
@@ -145,7 +225,7 @@ impl<'source> Parser<'source> {
         move |original| {
             // Here we use "token insertion" as a recovery strategy. If we expect a token X, but find a token Y, and Y
             // would be valid after X, we pretend that we "inserted" X. That being said, we still need to report the
-            // error.
+            // error. Y is left in the stream, so that it can be parsed as usual.
             let found = recover.match_pattern(self, token).or(Err(original))?;
             let error = self
                 .error()
@@ -160,4 +240,5 @@ impl<'source> Parser<'source> {
     }
 }
 
+#[derive(Debug, Clone, Copy)]
 pub struct Cursor(usize);
@@ -1,8 +1,21 @@
 use crate::{
-    error::ErrorVariant,
-    span::{Spanned, SpannedToken},
+    ast::Pattern,
+    error::{Error, ErrorVariant},
+    parser::Cursor,
+    span::{Span, Spanned, SpannedToken},
     token_info::Precedence,
-    BinaryExpression, Expression, Parser, SpannedTokenExt, TokenInfoExt, UnaryExpression,
+    Argument, ArrayExpression, ArrayRepeatExpression, ArrayType, AscriptionExpression,
+    AssignExpression, Attribute, AttributeKind, AttributeStyle, BinaryExpression, BindingPattern,
+    BlockExpression, BreakExpression, Call, CastExpression, ConstructorPattern, ContinueExpression,
+    DelimitedTree, Expression, ExpressionStatement, FieldInitializer, FieldPattern, ForExpression,
+    FunctionItem, FunctionSignature, FunctionType, GenericParameter, GenericType, Identifer,
+    IfExpression, IndexExpression, InterpolatedString, IsExpression, Item, Label, LambdaExpression,
+    LetStatement, LiteralPattern, LoopExpression, MatchArm, MatchExpression, Module, ModuleItem,
+    NamedArgument, NamedField, OrPattern, Parameter, Parser, Path, PathExpression, PathSegment,
+    PipelineExpression, RangeExpression, RangePattern, ReturnExpression, SpannedTokenExt,
+    StringPart, StructExpression, StructFields, StructItem, StructPattern, TokenInfoExt, TokenTree,
+    TupleExpression, TupleField, TuplePattern, TupleType, TypeExpr, UnaryExpression, UnitLiteral,
+    WhileExpression, WordPattern,
 };
 use lexer::{token_category, token_category_slice, Token};
 use std::convert::TryInto;
@@ -10,7 +23,45 @@ use std::convert::TryInto;
 // Something really bugs me about the actual parser implementation being in the same file as its definition, and I don't
 // know why. I know it's a bit of a strange choice, but that's why this is a different module.
 
-const BEGINS_EXPRESSION: &'static [Token] = token_category_slice![FirstTokenOfExpression];
+const BEGINS_EXPRESSION: &[Token] = token_category_slice![FirstTokenOfExpression];
+const BEGINS_ITEM: &[Token] = token_category_slice![ItemKeyword];
+const BEGINS_PATTERN: &[Token] = token_category_slice![FirstTokenOfPattern];
+// A number in a pattern can be negative, like `-1`, so a literal pattern can start with a `-`.
+const BEGINS_LITERAL_PATTERN: &[Token] =
+    &[Token::String, Token::Integer, Token::Float, Token::Minus];
+const NUMBERS: &[Token] = &[Token::Integer, Token::Float];
+const BEGINS_ATTRIBUTE: &[Token] = &[Token::Hash, Token::OuterDocComment, Token::InnerDocComment];
+const BEGINS_TYPE: &[Token] = &[
+    Token::Identifier,
+    Token::OpeningParen,
+    Token::OpeningBracket,
+    Token::Function,
+];
+const BEGINS_BLOCK_LIKE: &[Token] = &[
+    Token::OpeningBrace,
+    Token::If,
+    Token::Match,
+    Token::While,
+    Token::Loop,
+    Token::For,
+    Token::Label,
+];
+const FOLLOWS_STATEMENT: &[Token] = &[Token::Semicolon, Token::ClosingBrace];
+const FOLLOWS_MATCH_ARM: &[Token] = &[Token::Comma, Token::ClosingBrace];
+const FOLLOWS_STRUCT_NAME: &[Token] = &[Token::OpeningBrace, Token::OpeningParen, Token::Semicolon];
+const FOLLOWS_MODULE_NAME: &[Token] = &[Token::OpeningBrace, Token::Semicolon];
+const FOLLOWS_ELSE: &[Token] = &[Token::If, Token::OpeningBrace];
+const FOLLOWS_TYPE: &[Token] = &[
+    Token::Comma,
+    Token::Semicolon,
+    Token::Equals,
+    Token::OpeningBrace,
+    Token::ClosingBrace,
+    Token::ClosingParen,
+    Token::ClosingBracket,
+    Token::Greater,
+    Token::Pipe,
+];
 
 macro_rules! bail {
     ($self:expr => $expr:expr ) => {{
@@ -32,49 +83,206 @@ macro_rules! bail {
 impl Parser<'_> {
     pub fn parse(&mut self) -> Expression {
         let result = self.parse_expression();
+        self.add_leftover_errors();
         self.add_delimiter_errors();
         result
     }
 
+    /// Parse an entire source file as a module.
+    pub fn parse_module(&mut self) -> Module {
+        let attributes = self.parse_inner_attributes();
+        let mut items = Vec::new();
+        while self.peek().is_ok() {
+            items.push(self.parse_item());
+        }
+
+        self.add_delimiter_errors();
+
+        Module {
+            span: Span::new(0, self.source().len()),
+            attributes,
+            items,
+        }
+    }
+
+    pub fn parse_item(&mut self) -> Item {
+        let attributes = self.parse_outer_attributes();
+        if let (Some(last), Err(_) | Ok(Token::ClosingBrace)) =
+            (attributes.last(), self.peek().token())
+        {
+            let span = attributes[0].span.union(last.span);
+            self.report_dangling_attributes(&attributes, "an item");
+            return Item::Error(span);
+        }
+
+        // Anything that can't begin an item is skipped over, up until the start of the next item. Delimiters are kept
+        // balanced while skipping, and it stops at the closing brace of an enclosing module, so that neither the items
+        // inside of a block nor the end of the module are skipped along with it.
+        let first = bail!(self => self.peek());
+        if self.peek_matches(BEGINS_ITEM).is_err() {
+            // A closing delimiter can't close anything here, so it needs to be skipped for the parser to make progress.
+            if let token_category![ClosingBracket] = first.kind() {
+                let _ = self.next();
+            }
+
+            self.skip_until(BEGINS_ITEM);
+
+            let span = first.span().union(self.span());
+            let error = self
+                .error()
+                .location(span)
+                .mismatch(BEGINS_ITEM, first)
+                .build()
+                .unwrap();
+
+            self.errors.push(error);
+            if self.peek_matches(BEGINS_ITEM).is_err() {
+                return Item::Error(span);
+            }
+        }
+
+        let token = bail!(self => self.next());
+
+        match token.kind() {
+            Token::Let => self.parse_let_statement(attributes, BEGINS_ITEM),
+            Token::Function => self.parse_function_item(attributes),
+            Token::Struct => self.parse_struct_item(attributes),
+            Token::Module => self.parse_module_item(attributes),
+            _ => unreachable!(),
+        }
+    }
+
     pub fn parse_expression(&mut self) -> Expression {
         self.parse_expression_with(Precedence::START)
     }
 
+    /// Parse an expression where one that starts out block-like ends right after the block, like a statement or the body
+    /// of a match arm. Otherwise, something like `if a { b } (c, d)` would be parsed as a call, rather than as two
+    /// separate expressions.
+    fn parse_statement_expression(&mut self) -> Expression {
+        match self.peek_matches(BEGINS_BLOCK_LIKE) {
+            Ok(_) => {
+                let token = bail!(self => self.next());
+                self.parse_prefix_expression(token)
+            }
+            Err(_) => self.parse_expression(),
+        }
+    }
+
     fn parse_expression_with(&mut self, precedence: Precedence) -> Expression {
+        // Attributes can only be put on items and statements, but if there are any here anyway, they're skipped over
+        // so that the expression after them can still be parsed.
+        for attribute in self.parse_outer_attributes() {
+            let error = self
+                .error()
+                .location(attribute.span)
+                .message("attributes are not allowed here")
+                .build()
+                .unwrap();
+
+            self.errors.push(error)
+        }
+
         let token = bail!(self => self.expect_matches(BEGINS_EXPRESSION));
+        self.parse_expression_from(token, precedence)
+    }
+
+    /// Parse an expression, or report an error and produce [Expression::Error] without consuming anything if the next
+    /// token matches `recover` instead.
+    fn parse_expression_or_recover_with(&mut self, recover: Token) -> Expression {
+        let token = bail!(self => self.expect_matches_or_recover_with(BEGINS_EXPRESSION, recover));
+        if !BEGINS_EXPRESSION.contains(&token.kind()) {
+            return Expression::error(token.span());
+        }
+
+        self.parse_expression_from(token, Precedence::START)
+    }
+
+    fn parse_expression_from(&mut self, token: SpannedToken, precedence: Precedence) -> Expression {
+        let is_range =
+            |token: SpannedToken| matches!(token.kind(), Token::DotDot | Token::DotDotEquals);
+
+        // Ranges don't associate, so `a..b..c` is rejected rather than quietly meaning `(a..b)..c`. A parenthesized range
+        // is parsed as a prefix expression, so a range is only chained if it was produced by this loop (or is a range
+        // with no start, like `..b`). Each chain is only reported once, however long it is.
+        let mut follows_range = is_range(token);
+        let mut chain = None;
         let mut expr = self.parse_prefix_expression(token);
 
-        while self.can_continue(precedence) {
+        while self.can_continue(&expr, precedence) {
             let token = bail!(self => self.next());
+            let chained = follows_range && is_range(token);
+            follows_range = is_range(token);
             expr = self.parse_infix_expression(expr, token);
+
+            if chained {
+                chain = Some(expr.span());
+            }
+        }
+
+        if let Some(span) = chain {
+            self.report_chained_range(span);
         }
 
         expr
     }
 
-    fn can_continue(&self, precedence: Precedence) -> bool {
-        self.peek()
-            .token()
-            .map_or(false, |token| precedence < token.precedence())
+    fn can_continue(&mut self, expr: &Expression, precedence: Precedence) -> bool {
+        match self.peek().token() {
+            // A `{` following a path begins a struct literal, but only where struct literals are allowed. Otherwise, the
+            // `{` is left alone, and ends the expression.
+            Ok(Token::OpeningBrace) if !(self.allow_struct_literals && expr.is_type_path()) => {
+                false
+            }
+            token => token.map_or(false, |token| precedence < token.precedence()),
+        }
     }
 
     #[inline]
     fn parse_prefix_expression(&mut self, token: SpannedToken) -> Expression {
         match token.kind() {
             Token::OpeningParen => self.parse_parenthesized_expression(token),
+            Token::OpeningBrace => self.parse_block_expression(token),
+            Token::OpeningBracket => self.parse_array_expression(token),
+            Token::Pipe => self.parse_lambda_expression(token),
+            Token::DotDot | Token::DotDotEquals => self.parse_range_expression(None, token),
+            Token::If => self.parse_if_expression(),
+            Token::Match => self.parse_match_expression(),
+            Token::While | Token::Loop | Token::For => {
+                let cursor = self.cursor();
+                self.parse_loop_expression(cursor, token, None)
+            }
+            Token::Label => self.parse_labelled_loop(token),
+            Token::Break => self.parse_break_expression(token),
+            Token::Continue => self.parse_continue_expression(token),
+            Token::Return => self.parse_return_expression(token),
             token_category![UnaryOperator] => self.parse_unary_expression(token),
             // SAFETY: All tokens that match these pattern can be converted into a literal/identifier,
             // so the unwrap will not fail.
+            Token::String => self.parse_string_literal(token),
             token_category![Literal] => Expression::Literal(token.try_into().unwrap()),
             Token::Identifier => Expression::Identifier(token.try_into().unwrap()),
-            _ => unreachable!()
+            _ => unreachable!(),
         }
     }
 
     #[inline]
     fn parse_infix_expression(&mut self, expr: Expression, token: SpannedToken) -> Expression {
         match token.kind() {
+            // `|>` is a binary operator as far as precedence goes, but it gets a node of its own.
+            Token::PipeGreater => self.parse_pipeline_expression(expr, token),
             token_category![BinaryOperator] => self.parse_binary_expression(expr, token),
+            Token::Equals | token_category![CompoundOperator] => {
+                self.parse_assign_expression(expr, token)
+            }
+            Token::OpeningParen => self.parse_call(expr, token),
+            Token::OpeningBracket => self.parse_index_expression(expr, token),
+            Token::Is => self.parse_is_expression(expr),
+            Token::As => self.parse_cast_expression(expr),
+            Token::Colon => self.parse_ascription_expression(expr),
+            Token::DotDot | Token::DotDotEquals => self.parse_range_expression(Some(expr), token),
+            token_category![Path] => self.parse_path_expression(expr, token),
+            Token::OpeningBrace => self.parse_struct_expression(expr, token),
             _ => Expression::error(token.span()),
         }
     }
@@ -93,7 +301,8 @@ impl Parser<'_> {
     }
 
     fn parse_binary_expression(&mut self, left: Expression, token: SpannedToken) -> Expression {
-        let right = self.parse_expression_with(token.kind().precedence());
+        let right = self
+            .parse_expression_with(token.kind().precedence().with(token.kind().associativity()));
 
         // SAFETY: Unwrapping below is safe, as this method will only be called if we have a token that we know can be
         // converted into a binary operator.
@@ -106,40 +315,2878 @@ impl Parser<'_> {
         .into()
     }
 
+    /// Parse a range expression. The start of the range (if there is one) and the `..` or `..=` are expected to have
+    /// already been consumed.
+    fn parse_range_expression(
+        &mut self,
+        start: Option<Expression>,
+        token: SpannedToken,
+    ) -> Expression {
+        let inclusive = token.kind() == Token::DotDotEquals;
+
+        // A range can end right before the `{` of a block, as in `for i in 0.. { }`, so a `{` only begins the end of
+        // the range where a struct literal could appear. An end that's a range of its own, as in `a.. ..b`, is chained
+        // just like `a..b..c` is (see `parse_expression_from`).
+        let mut chained = false;
+        let has_end = match self.peek().token() {
+            Ok(Token::OpeningBrace) => self.allow_struct_literals,
+            Ok(token) => {
+                chained |= token == Token::DotDot || token == Token::DotDotEquals;
+                BEGINS_EXPRESSION.contains(&token)
+            }
+            Err(_) => false,
+        };
+
+        let end = match has_end {
+            true => Some(self.parse_expression_with(Precedence::RANGE)),
+            false => None,
+        };
+
+        if inclusive && end.is_none() {
+            let error = self
+                .error()
+                .location(token.span())
+                .message("an inclusive range needs an end")
+                .build()
+                .unwrap();
+
+            self.errors.push(error);
+        }
+
+        let start_span = start.as_ref().map_or(token.span(), Spanned::span);
+        let end_span = end.as_ref().map_or(token.span(), Spanned::span);
+
+        if chained {
+            self.report_chained_range(start_span.union(end_span));
+        }
+
+        RangeExpression {
+            span: start_span.union(end_span),
+            start: start.map(Box::new),
+            end: end.map(Box::new),
+            inclusive,
+        }
+        .into()
+    }
+
+    fn report_chained_range(&mut self, span: Span) {
+        let error = self
+            .error()
+            .location(span)
+            .message("ranges can't be chained without parentheses")
+            .build()
+            .unwrap();
+
+        self.errors.push(error);
+    }
+
+    fn parse_assign_expression(&mut self, target: Expression, token: SpannedToken) -> Expression {
+        if !target.is_assignable() && !target.is_error() {
+            let error = self
+                .error()
+                .location(target.span())
+                .message("invalid assignment target")
+                .build()
+                .unwrap();
+
+            self.errors.push(error);
+        }
+
+        let value = self
+            .parse_expression_with(token.kind().precedence().with(token.kind().associativity()));
+
+        // SAFETY: Unwrapping below is safe, as this method will only be called if we have a token that we know can be
+        // converted into an assignment operator.
+        AssignExpression {
+            span: target.span().union(value.span()),
+            operator: token.try_into().unwrap(),
+            target: Box::new(target),
+            value: Box::new(value),
+        }
+        .into()
+    }
+
+    fn parse_pipeline_expression(&mut self, input: Expression, token: SpannedToken) -> Expression {
+        let function = self
+            .parse_expression_with(token.kind().precedence().with(token.kind().associativity()));
+
+        PipelineExpression {
+            span: input.span().union(function.span()),
+            input: Box::new(input),
+            function: Box::new(function),
+        }
+        .into()
+    }
+
+    fn parse_cast_expression(&mut self, expression: Expression) -> Expression {
+        let target = self.parse_expression_type();
+
+        CastExpression {
+            span: expression.span().union(target.span()),
+            expression: Box::new(expression),
+            target,
+        }
+        .into()
+    }
+
+    fn parse_ascription_expression(&mut self, expression: Expression) -> Expression {
+        let annotation = self.parse_expression_type();
+
+        AscriptionExpression {
+            span: expression.span().union(annotation.span()),
+            expression: Box::new(expression),
+            annotation,
+        }
+        .into()
+    }
+
+    fn parse_is_expression(&mut self, expression: Expression) -> Expression {
+        let pattern = self.parse_pattern();
+
+        IsExpression {
+            span: expression.span().union(pattern.span()),
+            expression: Box::new(expression),
+            pattern,
+        }
+        .into()
+    }
+
+    fn parse_path_expression(&mut self, root: Expression, token: SpannedToken) -> Expression {
+        let name = bail!(self => self.expect_matches(Token::Identifier));
+
+        // SAFETY: The unwraps below will not fail, as this method is only called with a path token, and the name is
+        // guaranteed to be an identifier.
+        let segment = PathSegment {
+            span: token.span().union(name.span()),
+            access: token.kind().try_into().unwrap(),
+            name: name.try_into().unwrap(),
+        };
+
+        // Consecutive segments are collected into a single path, rather than nesting a new path for each one.
+        let (root, mut segments) = match root {
+            Expression::Path(path) => (path.root, path.segments),
+            root => (Box::new(root), Vec::new()),
+        };
+
+        segments.push(segment);
+
+        PathExpression {
+            span: root.span().union(name.span()),
+            root,
+            segments,
+        }
+        .into()
+    }
+
+    fn parse_struct_expression(&mut self, name: Expression, token: SpannedToken) -> Expression {
+        let (fields, closing) = bail!(self => self.parse_separated(
+            token,
+            Token::ClosingBrace,
+            Self::parse_field_initializer,
+        ));
+
+        StructExpression {
+            span: name.span().union(closing.span()),
+            name: Box::new(name),
+            fields: fields.into_iter().flatten().collect(),
+        }
+        .into()
+    }
+
+    fn parse_field_initializer(&mut self) -> Option<FieldInitializer> {
+        let name = bail!(self => self.expect_matches(Token::Identifier));
+
+        let value = match self.peek_matches(Token::Colon) {
+            Ok(_) => {
+                let _ = self.next();
+                Some(Box::new(self.parse_expression()))
+            }
+            Err(_) => None,
+        };
+
+        // SAFETY: The name is guaranteed to be an identifier, so the unwrap will not fail.
+        Some(FieldInitializer {
+            span: value
+                .as_ref()
+                .map_or(name.span(), |value| name.span().union(value.span())),
+            name: name.try_into().unwrap(),
+            value,
+        })
+    }
+
+    /// Parse either a unit literal `()`, a tuple, or an expression in parentheses.
     fn parse_parenthesized_expression(&mut self, token: SpannedToken) -> Expression {
+        if let Ok(closing) = self.peek_matches(Token::ClosingParen) {
+            let _ = self.next();
+            return Expression::Literal(
+                UnitLiteral::new(token.span().union(closing.span())).into(),
+            );
+        }
+
         self.unclosed_delimiters.push(token);
-        let expression = self.parse_expression();
-        match self.expect_matches(Token::ClosingParen) {
+
+        let mut elements = Vec::new();
+        let mut trailing_comma;
+
+        let closing = loop {
+            elements.push(self.with_struct_literals(true, Self::parse_expression));
+            trailing_comma = false;
+
+            let next = bail!(self => self.peek());
+            match next.kind() {
+                Token::ClosingParen => break bail!(self => self.next()),
+                Token::Comma => {
+                    let _ = self.next();
+                    trailing_comma = true;
+
+                    if let Ok(closing) = self.peek_matches(Token::ClosingParen) {
+                        let _ = self.next();
+                        break closing;
+                    }
+                }
+                // Another element right after this one most likely means that a comma was forgotten, so we report it
+                // and carry on as if it were there.
+                token_category![FirstTokenOfExpression] => {
+                    let previous = elements.last().map_or(token.span(), Spanned::span);
+                    let error = self
+                        .error()
+                        .location(previous.union(next.span()))
+                        .message("expected a `,` between these elements")
+                        .build()
+                        .unwrap();
+
+                    self.errors.push(error);
+                }
+                _ => match self.expect_matches(&[Token::Comma, Token::ClosingParen][..]) {
+                    Ok(separator) if separator.kind() == Token::ClosingParen => break separator,
+                    Ok(_) => (),
+                    Err(error) => return ErrorVariant::error(token.span().union(error.span())),
+                },
+            }
+        };
+
+        self.unclosed_delimiters.pop();
+
+        // Without a trailing comma, a single expression in parentheses is just that expression, not a tuple.
+        if elements.len() == 1 && !trailing_comma {
+            return elements.remove(0);
+        }
+
+        TupleExpression {
+            span: token.span().union(closing.span()),
+            elements,
+        }
+        .into()
+    }
+
+    /// Parse a string literal, along with any expressions interpolated into it.
+    fn parse_string_literal(&mut self, token: SpannedToken) -> Expression {
+        // The contents of the string, not including its quotes.
+        let (start, end) = (token.span().start() + 1, token.span().end() - 1);
+        let bytes = self.lexer.source().as_bytes();
+
+        let mut parts = Vec::new();
+        let mut interpolated = false;
+        let mut literal_start = start;
+        let mut index = start;
+
+        while index < end {
+            match (bytes[index], bytes.get(index + 1)) {
+                (b'\\', _) | (b'{', Some(b'{')) | (b'}', Some(b'}')) => index += 2,
+                (b'{', _) => {
+                    if literal_start < index {
+                        parts.push(StringPart::Literal(Span::new(literal_start, index)));
+                    }
+
+                    let (expression, next) = self.parse_interpolation(index, end);
+                    parts.push(StringPart::Expression(expression));
+
+                    interpolated = true;
+                    literal_start = next;
+                    index = next;
+                }
+                _ => index += 1,
+            }
+        }
+
+        if !interpolated {
+            // SAFETY: The token is guaranteed to be a string, so the unwrap will not fail.
+            return Expression::Literal(token.try_into().unwrap());
+        }
+
+        if literal_start < end {
+            parts.push(StringPart::Literal(Span::new(literal_start, end)));
+        }
+
+        InterpolatedString {
+            span: token.span(),
+            parts,
+        }
+        .into()
+    }
+
+    /// Parse the expression in an interpolation that begins at `open`, within a string whose contents end at `end`.
+    /// This produces the expression, along with the position right after the interpolation's closing `}`.
+    fn parse_interpolation(&mut self, open: usize, end: usize) -> (Expression, usize) {
+        // The interpolation is parsed by a parser of its own, which only sees the source up to the end of the string,
+        // but still starts at the same place so that all of its spans line up with ours.
+        let mut parser = Parser::new(&self.lexer.source()[..end]);
+        parser.lexer.bump(open + 1);
+        parser.loop_labels = self.loop_labels.clone();
+
+        let expression = match parser.peek_matches(Token::ClosingBrace) {
+            Ok(closing) => {
+                let span = Span::new(open, closing.span().end());
+                let error = parser
+                    .error()
+                    .location(span)
+                    .message("expected an expression inside of `{}`")
+                    .build()
+                    .unwrap();
+
+                parser.errors.push(error);
+                Expression::error(span)
+            }
+            Err(_) => parser.parse_expression(),
+        };
+
+        match parser.expect_matches(Token::ClosingBrace) {
+            Ok(closing) => {
+                parser.add_delimiter_errors();
+                self.errors.extend(parser.errors);
+                (expression, closing.span().end())
+            }
+            Err(_) => {
+                // Whatever the interpolation's parser made of the rest of the string isn't worth reporting, since it
+                // was never meant to be a complete expression.
+                let span = Span::new(open, open + 1);
+                let error = self
+                    .error()
+                    .location(span)
+                    .message("unclosed `{` in string interpolation")
+                    .build()
+                    .unwrap();
+
+                self.errors.push(error);
+                (Expression::error(Span::new(open, end)), end)
+            }
+        }
+    }
+
+    fn parse_lambda_expression(&mut self, opening: SpannedToken) -> Expression {
+        // A lambda's body isn't inside of any loop that the lambda itself is in, so `break` and `continue` can't jump
+        // out of it.
+        let loop_labels = std::mem::take(&mut self.loop_labels);
+        let lambda = self.parse_lambda_contents(opening);
+        self.loop_labels = loop_labels;
+        lambda
+    }
+
+    fn parse_lambda_contents(&mut self, opening: SpannedToken) -> Expression {
+        let (parameters, _) = bail!(self => self.parse_separated(
+            opening,
+            Token::Pipe,
+            Self::parse_lambda_parameter,
+        ));
+
+        // Like in Rust, a lambda with a return type needs a block for its body, since otherwise something like
+        // `|x| -> Int x` would be very hard to read.
+        let (return_type, body) = match self.peek_matches(Token::Arrow) {
             Ok(_) => {
-                self.unclosed_delimiters.pop();
-                expression
+                let _ = self.next();
+                let return_type = self.parse_type();
+                let token = bail!(self => self.expect_matches(Token::OpeningBrace));
+                (Some(return_type), self.parse_block_expression(token))
+            }
+            Err(_) => (None, self.parse_expression()),
+        };
+
+        LambdaExpression {
+            span: opening.span().union(body.span()),
+            parameters,
+            return_type,
+            body: Box::new(body),
+        }
+        .into()
+    }
+
+    fn parse_lambda_parameter(&mut self) -> Parameter {
+        // Or-patterns aren't allowed here, since their `|` would be ambiguous with the end of the parameter list.
+        let pattern = self.parse_pattern_alternative();
+        let mut span = pattern.span();
+
+        let annotation = match self.peek_matches(Token::Colon) {
+            Ok(_) => {
+                let _ = self.next();
+                let annotation = self.parse_type();
+                span = span.union(annotation.span());
+                Some(annotation)
+            }
+            Err(_) => None,
+        };
+
+        Parameter {
+            span,
+            pattern,
+            annotation,
+            default: None,
+        }
+    }
+
+    fn parse_array_expression(&mut self, opening: SpannedToken) -> Expression {
+        if let Ok(closing) = self.peek_matches(Token::ClosingBracket) {
+            let _ = self.next();
+            return ArrayExpression {
+                span: opening.span().union(closing.span()),
+                elements: Vec::new(),
+            }
+            .into();
+        }
+
+        self.unclosed_delimiters.push(opening);
+        let first = self.with_struct_literals(true, Self::parse_expression);
+
+        if self.peek_matches(Token::Semicolon).is_ok() {
+            let _ = self.next();
+            let count = self.with_struct_literals(true, Self::parse_expression);
+            let closing = bail!(self => self.expect_matches(Token::ClosingBracket));
+            self.unclosed_delimiters.pop();
+
+            return ArrayRepeatExpression {
+                span: opening.span().union(closing.span()),
+                value: Box::new(first),
+                count: Box::new(count),
+            }
+            .into();
+        }
+
+        // Once we know this isn't a repeat, the rest of the elements are parsed like any other list.
+        let separator =
+            bail!(self => self.expect_matches(&[Token::Comma, Token::ClosingBracket][..]));
+        self.unclosed_delimiters.pop();
+        if separator.kind() == Token::ClosingBracket {
+            return ArrayExpression {
+                span: opening.span().union(separator.span()),
+                elements: vec![first],
             }
-            Err(error) => ErrorVariant::error(token.span().union(error.span())),
+            .into();
+        }
+
+        let (rest, closing) = bail!(self => self.parse_separated(
+            opening,
+            Token::ClosingBracket,
+            Self::parse_expression,
+        ));
+
+        let mut elements = vec![first];
+        elements.extend(rest);
+
+        ArrayExpression {
+            span: opening.span().union(closing.span()),
+            elements,
+        }
+        .into()
+    }
+
+    fn parse_index_expression(&mut self, operand: Expression, token: SpannedToken) -> Expression {
+        self.unclosed_delimiters.push(token);
+        let index = self.with_struct_literals(true, Self::parse_expression);
+        let closing = bail!(self => self.expect_matches(Token::ClosingBracket));
+        self.unclosed_delimiters.pop();
+
+        IndexExpression {
+            span: operand.span().union(closing.span()),
+            operand: Box::new(operand),
+            index: Box::new(index),
         }
+        .into()
+    }
+
+    fn parse_block_expression(&mut self, token: SpannedToken) -> Expression {
+        // Struct literals are always allowed inside of a block, even when the block itself is somewhere they aren't.
+        self.with_struct_literals(true, |parser| parser.parse_block_contents(token))
     }
 
-    // TODO: finish writing this
-    fn parse_call(&mut self, token: SpannedToken) -> Expression {
+    fn parse_block_contents(&mut self, token: SpannedToken) -> Expression {
         self.unclosed_delimiters.push(token);
 
+        let cursor = self.cursor();
+        let mut statements = Vec::new();
+        let mut tail = None;
+
         loop {
-            let token = bail!(self => self.next());
-            if token.kind() == Token::Identifier {
-                
+            let next = bail!(self => self.peek());
+            match next.kind() {
+                Token::ClosingBrace => {
+                    let _ = self.next();
+                    break;
+                }
+                // Empty statements are allowed, and don't produce anything.
+                Token::Semicolon => {
+                    let _ = self.next();
+                    continue;
+                }
+                _ => (),
+            }
+
+            let attributes = self.parse_outer_attributes();
+            let next = bail!(self => self.peek());
+            if next.kind() == Token::ClosingBrace {
+                self.report_dangling_attributes(&attributes, "a statement");
+                continue;
+            }
+
+            if next.kind() == Token::Let {
+                let _ = self.next();
+                statements.push(self.parse_let_statement(attributes, &[Token::ClosingBrace]));
+                continue;
+            }
+
+            let expression = self.parse_statement_expression();
+            let statement = ExpressionStatement {
+                attributes,
+                expression,
+            };
+
+            // Expressions that end in a block don't need to be terminated with a semicolon when they're used as a
+            // statement, much like in Rust.
+            if statement.expression.is_block_like() && self.peek_matches(FOLLOWS_STATEMENT).is_err()
+            {
+                statements.push(statement.into());
+                continue;
+            }
+
+            let separator = bail!(self => self.expect_matches(FOLLOWS_STATEMENT));
+            if separator.kind() == Token::ClosingBrace {
+                for attribute in statement.attributes {
+                    let error = self
+                        .error()
+                        .location(attribute.span)
+                        .message("attributes are not allowed on the tail expression of a block")
+                        .build()
+                        .unwrap();
+
+                    self.errors.push(error)
+                }
+
+                tail = Some(Box::new(statement.expression));
+                break;
+            }
+
+            statements.push(statement.into());
+        }
+
+        self.unclosed_delimiters.pop();
+
+        BlockExpression {
+            span: self.measure(cursor),
+            statements,
+            tail,
+        }
+        .into()
+    }
+
+    /// Parse an `if` expression, along with any `else if`/`else` branches that follow it. The `if` keyword is expected
+    /// to have already been consumed.
+    fn parse_if_expression(&mut self) -> Expression {
+        let cursor = self.cursor();
+        let condition = self.parse_condition();
+
+        let token = bail!(self => self.expect_matches(Token::OpeningBrace));
+        let then_branch = self.parse_block_expression(token);
+
+        let else_branch = match self.peek_matches(Token::Else) {
+            Ok(_) => {
+                let _ = self.next();
+                let token = bail!(self => self.expect_matches(FOLLOWS_ELSE));
+                let branch = match token.kind() {
+                    Token::If => self.parse_if_expression(),
+                    _ => self.parse_block_expression(token),
+                };
+
+                Some(Box::new(branch))
             }
+            Err(_) => None,
+        };
+
+        IfExpression {
+            span: self.measure(cursor),
+            condition: Box::new(condition),
+            then_branch: Box::new(then_branch),
+            else_branch,
         }
+        .into()
     }
 
-    fn add_delimiter_errors(&mut self) {
-        for unclosed in self.unclosed_delimiters.clone() {
-            let error = self
-                .error()
-                .location(unclosed.span())
-                .message("unclosed delimiter")
-                .build()
-                .unwrap();
+    /// Parse a loop with a label, like `'outer: loop { }`. The label is expected to have already been consumed.
+    fn parse_labelled_loop(&mut self, label: SpannedToken) -> Expression {
+        let cursor = self.cursor();
+        bail!(self => self.expect_matches(Token::Colon));
+        let token =
+            bail!(self => self.expect_matches(&[Token::While, Token::Loop, Token::For][..]));
 
-            self.errors.push(error)
+        self.parse_loop_expression(cursor, token, Some(label))
+    }
+
+    /// Parse a `while`, `loop` or `for` loop, starting from `cursor`. The keyword is expected to have already been
+    /// consumed.
+    fn parse_loop_expression(
+        &mut self,
+        cursor: Cursor,
+        keyword: SpannedToken,
+        label: Option<SpannedToken>,
+    ) -> Expression {
+        // The header of a loop isn't inside of the loop itself, so it's parsed before the label is pushed.
+        let header = match keyword.kind() {
+            Token::While => Some((None, self.parse_condition())),
+            Token::For => {
+                let pattern = self.parse_pattern();
+                bail!(self => self.expect_matches(Token::In));
+                Some((Some(pattern), self.parse_condition()))
+            }
+            _ => None,
+        };
+
+        let token = bail!(self => self.expect_matches(Token::OpeningBrace));
+        self.loop_labels.push(label);
+        let body = Box::new(self.parse_block_expression(token));
+        self.loop_labels.pop();
+
+        let span = self.measure(cursor);
+        // SAFETY: The label is guaranteed to be a label token, so the unwrap will not fail.
+        let label = label.map(|label| label.try_into().unwrap());
+
+        match header {
+            Some((Some(pattern), iterable)) => ForExpression {
+                span,
+                label,
+                pattern,
+                iterable: Box::new(iterable),
+                body,
+            }
+            .into(),
+            Some((None, condition)) => WhileExpression {
+                span,
+                label,
+                condition: Box::new(condition),
+                body,
+            }
+            .into(),
+            None => LoopExpression { span, label, body }.into(),
         }
     }
+
+    fn parse_break_expression(&mut self, keyword: SpannedToken) -> Expression {
+        let label = self.parse_jump_label(keyword);
+        let value = match self.peek_matches(BEGINS_EXPRESSION) {
+            Ok(_) => Some(Box::new(self.parse_expression())),
+            Err(_) => None,
+        };
+
+        let end = value
+            .as_ref()
+            .map(|value| value.span())
+            .or_else(|| label.as_ref().map(Spanned::span))
+            .unwrap_or_else(|| keyword.span());
+
+        BreakExpression {
+            span: keyword.span().union(end),
+            label,
+            value,
+        }
+        .into()
+    }
+
+    fn parse_continue_expression(&mut self, keyword: SpannedToken) -> Expression {
+        let label = self.parse_jump_label(keyword);
+        let end = label.as_ref().map_or(keyword.span(), Spanned::span);
+
+        ContinueExpression {
+            span: keyword.span().union(end),
+            label,
+        }
+        .into()
+    }
+
+    fn parse_return_expression(&mut self, keyword: SpannedToken) -> Expression {
+        let value = match self.peek_matches(BEGINS_EXPRESSION) {
+            Ok(_) => Some(Box::new(self.parse_expression())),
+            Err(_) => None,
+        };
+
+        let end = value.as_ref().map_or(keyword.span(), |value| value.span());
+        ReturnExpression {
+            span: keyword.span().union(end),
+            value,
+        }
+        .into()
+    }
+
+    /// Parse the optional label after a `break` or `continue`, and check that the jump is actually inside of a loop
+    /// with that label.
+    fn parse_jump_label(&mut self, keyword: SpannedToken) -> Option<Label> {
+        let label = match self.peek_matches(Token::Label) {
+            Ok(label) => {
+                let _ = self.next();
+                Some(label)
+            }
+            Err(_) => None,
+        };
+
+        let message = match label {
+            _ if self.loop_labels.is_empty() => Some(format!(
+                "`{}` can only be used inside of a loop",
+                &self.source()[keyword.span()]
+            )),
+            Some(label) => {
+                let name = &self.source()[label.span()];
+                let found = self
+                    .loop_labels
+                    .iter()
+                    .flatten()
+                    .any(|enclosing| &self.source()[enclosing.span()] == name);
+
+                match found {
+                    true => None,
+                    false => Some(format!("there is no enclosing loop labelled `{}`", name)),
+                }
+            }
+            None => None,
+        };
+
+        if let Some(message) = message {
+            let location = label.map_or(keyword.span(), |label| keyword.span().union(label.span()));
+            let error = self
+                .error()
+                .location(location)
+                .message(message)
+                .build()
+                .unwrap();
+
+            self.errors.push(error);
+        }
+
+        // SAFETY: The label is guaranteed to be a label token, so the unwrap will not fail.
+        label.map(|label| label.try_into().unwrap())
+    }
+
+    /// Parse a `match` expression. The `match` keyword is expected to have already been consumed.
+    fn parse_match_expression(&mut self) -> Expression {
+        let cursor = self.cursor();
+        let scrutinee = self.parse_condition();
+
+        let token = bail!(self => self.expect_matches(Token::OpeningBrace));
+        self.unclosed_delimiters.push(token);
+
+        let mut arms = Vec::new();
+
+        loop {
+            let next = bail!(self => self.peek());
+            if next.kind() == Token::ClosingBrace {
+                let _ = self.next();
+                break;
+            }
+
+            // If an arm is malformed, we skip over whatever is left of it and carry on with the next one.
+            let is_block_like = match self.with_struct_literals(true, Self::parse_match_arm) {
+                Some(arm) => {
+                    let is_block_like = arm.body.is_block_like();
+                    arms.push(arm);
+                    is_block_like
+                }
+                None => {
+                    self.skip_until(FOLLOWS_MATCH_ARM);
+                    false
+                }
+            };
+
+            // Like statements, arms with a block-like body don't need to be followed by a comma.
+            if is_block_like && self.peek_matches(FOLLOWS_MATCH_ARM).is_err() {
+                continue;
+            }
+
+            let separator = bail!(self => self.expect_matches(FOLLOWS_MATCH_ARM));
+            if separator.kind() == Token::ClosingBrace {
+                break;
+            }
+        }
+
+        self.unclosed_delimiters.pop();
+
+        MatchExpression {
+            span: self.measure(cursor),
+            scrutinee: Box::new(scrutinee),
+            arms,
+        }
+        .into()
+    }
+
+    fn parse_match_arm(&mut self) -> Option<MatchArm> {
+        if let Err(error) = self.peek_matches(BEGINS_PATTERN) {
+            self.errors.push(error);
+            return None;
+        }
+
+        let pattern = self.parse_pattern();
+
+        let guard = match self.peek_matches(Token::If) {
+            Ok(_) => {
+                let _ = self.next();
+                Some(Box::new(self.parse_expression()))
+            }
+            Err(_) => None,
+        };
+
+        if let Err(error) = self.expect_matches_or_recover_with(Token::FatArrow, BEGINS_EXPRESSION)
+        {
+            self.errors.push(error);
+            return None;
+        }
+
+        let body = self.parse_statement_expression();
+
+        Some(MatchArm {
+            span: pattern.span().union(body.span()),
+            pattern,
+            guard,
+            body: Box::new(body),
+        })
+    }
+
+    /// Parse the condition of a conditional expression, or the scrutinee of a `match`. This is parsed at [Precedence::CONDITIONAL], so that the
+    /// condition can't contain anything that binds more loosely than the conditional itself.
+    ///
+    /// A `{` following the condition always begins the body of the conditional, so struct literals aren't allowed here
+    /// unless they're parenthesized. Otherwise, something like `if x { ... }` would be ambiguous.
+    fn parse_condition(&mut self) -> Expression {
+        self.with_struct_literals(false, |parser| {
+            parser.parse_expression_with(Precedence::CONDITIONAL)
+        })
+    }
+
+    /// Run `parse` with struct literals either allowed or disallowed, restoring the previous setting afterwards.
+    fn with_struct_literals<T>(&mut self, allow: bool, parse: impl FnOnce(&mut Self) -> T) -> T {
+        let previous = std::mem::replace(&mut self.allow_struct_literals, allow);
+        let result = parse(self);
+        self.allow_struct_literals = previous;
+        result
+    }
+
+    /// Parse a function item. The `fn` keyword is expected to have already been consumed.
+    fn parse_function_item(&mut self, attributes: Vec<Attribute>) -> Item {
+        let cursor = self.cursor();
+        let name = bail!(self => self.expect_matches(Token::Identifier));
+        let generics = self.parse_generic_parameters();
+
+        let token = bail!(self => self.expect_matches(Token::OpeningParen));
+        let (parameters, _) =
+            bail!(self => self.parse_separated(token, Token::ClosingParen, Self::parse_parameter));
+
+        let return_type = match self.peek_matches(Token::Arrow) {
+            Ok(_) => {
+                let _ = self.next();
+                Some(self.parse_type())
+            }
+            Err(_) => None,
+        };
+
+        // SAFETY: The name is guaranteed to be an identifier, so the unwrap will not fail.
+        let signature = FunctionSignature {
+            span: self.measure(cursor),
+            name: name.try_into().unwrap(),
+            generics,
+            parameters,
+            return_type,
+        };
+
+        let token = bail!(self => self.expect_matches(Token::OpeningBrace));
+        let body = self.parse_block_expression(token);
+
+        FunctionItem {
+            span: self.measure(cursor),
+            attributes,
+            signature,
+            body: Box::new(body),
+        }
+        .into()
+    }
+
+    /// Parse a module item. This is either an inline module with a body, or a declaration of a module that lives in
+    /// another file. The `module` keyword is expected to have already been consumed.
+    fn parse_module_item(&mut self, attributes: Vec<Attribute>) -> Item {
+        let cursor = self.cursor();
+        let name = bail!(self => self.expect_matches(Token::Identifier));
+
+        let token = bail!(self => self.expect_matches(FOLLOWS_MODULE_NAME));
+        let body = match token.kind() {
+            Token::OpeningBrace => {
+                self.unclosed_delimiters.push(token);
+
+                let body_cursor = self.cursor();
+                let body_attributes = self.parse_inner_attributes();
+                let mut items = Vec::new();
+
+                loop {
+                    let next = bail!(self => self.peek());
+                    if next.kind() == Token::ClosingBrace {
+                        let _ = self.next();
+                        break;
+                    }
+
+                    items.push(self.parse_item());
+                }
+
+                self.unclosed_delimiters.pop();
+
+                Some(Module {
+                    span: self.measure(body_cursor),
+                    attributes: body_attributes,
+                    items,
+                })
+            }
+            _ => None,
+        };
+
+        // SAFETY: The name is guaranteed to be an identifier, so the unwrap will not fail.
+        ModuleItem {
+            span: self.measure(cursor),
+            attributes,
+            name: name.try_into().unwrap(),
+            body,
+        }
+        .into()
+    }
+
+    /// Parse a struct item. The `struct` keyword is expected to have already been consumed.
+    fn parse_struct_item(&mut self, attributes: Vec<Attribute>) -> Item {
+        let cursor = self.cursor();
+        let name = bail!(self => self.expect_matches(Token::Identifier));
+        let generics = self.parse_generic_parameters();
+
+        let token = bail!(self => self.expect_matches(FOLLOWS_STRUCT_NAME));
+        let fields = match token.kind() {
+            Token::OpeningBrace => {
+                let (fields, _) = bail!(self => self.parse_separated(
+                    token,
+                    Token::ClosingBrace,
+                    Self::parse_named_field,
+                ));
+
+                StructFields::Named(fields.into_iter().flatten().collect())
+            }
+            Token::OpeningParen => {
+                let (fields, _) = bail!(self => self.parse_separated(
+                    token,
+                    Token::ClosingParen,
+                    Self::parse_tuple_field,
+                ));
+
+                bail!(self => self.expect_matches_or_recover_with(Token::Semicolon, BEGINS_ITEM));
+                StructFields::Tuple(fields)
+            }
+            _ => StructFields::Unit,
+        };
+
+        // SAFETY: The name is guaranteed to be an identifier, so the unwrap will not fail.
+        StructItem {
+            span: self.measure(cursor),
+            attributes,
+            name: name.try_into().unwrap(),
+            generics,
+            fields,
+        }
+        .into()
+    }
+
+    /// Parse the generic parameters of a function or struct, if there are any. Like generic arguments, these are usually
+    /// written in square brackets, but angle brackets are accepted too.
+    fn parse_generic_parameters(&mut self) -> Vec<GenericParameter> {
+        let opening = match self.peek() {
+            Ok(token) if matches!(token.kind(), Token::OpeningBracket | Token::Lesser) => token,
+            _ => return Vec::new(),
+        };
+
+        let _ = self.next();
+        let closing = match opening.kind() {
+            Token::OpeningBracket => Token::ClosingBracket,
+            _ => Token::Greater,
+        };
+
+        let parameters = match self.parse_separated(opening, closing, Self::parse_generic_parameter)
+        {
+            Ok((parameters, _)) => parameters.into_iter().flatten().collect::<Vec<_>>(),
+            Err(error) => {
+                self.errors.push(error);
+                return Vec::new();
+            }
+        };
+
+        if let Some((_, leading)) = parameters.split_last() {
+            for parameter in leading.iter().filter(|parameter| parameter.variadic) {
+                let error = self
+                    .error()
+                    .location(parameter.span)
+                    .message("a variadic generic parameter must come last")
+                    .build()
+                    .unwrap();
+
+                self.errors.push(error);
+            }
+        }
+
+        parameters
+    }
+
+    fn parse_generic_parameter(&mut self) -> Option<GenericParameter> {
+        let start = bail!(self => self.peek()).span();
+        let variadic = match self.peek_matches(Token::DotDot) {
+            Ok(_) => {
+                let _ = self.next();
+                true
+            }
+            Err(_) => false,
+        };
+
+        let name = bail!(self => self.expect_matches(Token::Identifier));
+
+        let mut bounds = Vec::new();
+        if self.peek_matches(Token::Colon).is_ok() {
+            let _ = self.next();
+            bounds.push(self.parse_type());
+
+            while self.peek_matches(Token::Plus).is_ok() {
+                let _ = self.next();
+                bounds.push(self.parse_type());
+            }
+        }
+
+        let default = match self.peek_matches(Token::Equals) {
+            Ok(_) => {
+                let _ = self.next();
+                Some(self.parse_type())
+            }
+            Err(_) => None,
+        };
+
+        let end = default
+            .as_ref()
+            .or_else(|| bounds.last())
+            .map_or(name.span(), TypeExpr::span);
+
+        // SAFETY: The name is guaranteed to be an identifier, so the unwrap will not fail.
+        Some(GenericParameter {
+            span: start.union(end),
+            variadic,
+            name: name.try_into().unwrap(),
+            bounds,
+            default,
+        })
+    }
+
+    fn parse_named_field(&mut self) -> Option<NamedField> {
+        let attributes = self.parse_outer_attributes();
+        let name = bail!(self => self.expect_matches(Token::Identifier));
+        bail!(self => self.expect_matches_or_recover_with(Token::Colon, Token::Identifier));
+        let annotation = self.parse_type();
+
+        // SAFETY: The name is guaranteed to be an identifier, so the unwrap will not fail.
+        Some(NamedField {
+            span: name.span().union(annotation.span()),
+            attributes,
+            name: name.try_into().unwrap(),
+            annotation,
+        })
+    }
+
+    fn parse_tuple_field(&mut self) -> TupleField {
+        let attributes = self.parse_outer_attributes();
+        let annotation = self.parse_type();
+
+        TupleField {
+            span: annotation.span(),
+            attributes,
+            annotation,
+        }
+    }
+
+    fn parse_parameter(&mut self) -> Parameter {
+        let pattern = self.parse_pattern();
+        let mut span = pattern.span();
+
+        let annotation = match self.peek_matches(Token::Colon) {
+            Ok(_) => {
+                let _ = self.next();
+                let annotation = self.parse_type();
+                span = span.union(annotation.span());
+                Some(annotation)
+            }
+            Err(_) => None,
+        };
+
+        let default = match self.peek_matches(Token::Equals) {
+            Ok(_) => {
+                let _ = self.next();
+                let default = self.parse_expression();
+                span = span.union(default.span());
+                Some(Box::new(default))
+            }
+            Err(_) => None,
+        };
+
+        Parameter {
+            span,
+            pattern,
+            annotation,
+            default,
+        }
+    }
+
+    /// Parse a `let` statement, up to and including its terminating semicolon. The `let` keyword is expected to have
+    /// already been consumed. If the semicolon is missing, but the next token matches `recover`, we act as if it was
+    /// there.
+    ///
+    /// This is generic over its output, since `let` can be used both as a statement and as an item.
+    fn parse_let_statement<T>(&mut self, attributes: Vec<Attribute>, recover: &'static [Token]) -> T
+    where
+        T: ErrorVariant + From<LetStatement>,
+    {
+        let cursor = self.cursor();
+        let pattern = self.parse_pattern();
+
+        let annotation = match self.peek_matches(Token::Colon) {
+            Ok(_) => {
+                let _ = self.next();
+                Some(self.parse_type())
+            }
+            Err(_) => None,
+        };
+
+        let value = match self.peek_matches(Token::Semicolon) {
+            // Something like `let x;`. There's no sensible way to recover the initializer, so we report it as missing.
+            Ok(semicolon) => self
+                .error()
+                .location(semicolon)
+                .message("missing initializer in `let` binding")
+                .finish()
+                .unwrap(),
+            // We can still make sense of something like `let x 5;` or `let x = ;` here, so we try to recover.
+            Err(_) => {
+                bail!(self => self.expect_matches_or_recover_with(Token::Equals, BEGINS_EXPRESSION));
+                self.parse_expression_or_recover_with(Token::Semicolon)
+            }
+        };
+
+        bail!(self => self.expect_matches_or_recover_with(Token::Semicolon, recover));
+
+        LetStatement {
+            span: self.measure(cursor),
+            attributes,
+            pattern,
+            annotation,
+            value: Box::new(value),
+        }
+        .into()
+    }
+
+    fn parse_call(&mut self, operand: Expression, token: SpannedToken) -> Expression {
+        let mut seen_named = false;
+        let (arguments, closing) = bail!(self => self.parse_separated(token, Token::ClosingParen, |parser| {
+            let argument = parser.parse_argument();
+            match &argument {
+                Argument::Named(_) => seen_named = true,
+                Argument::Positional(expression) if seen_named => {
+                    let error = parser
+                        .error()
+                        .location(expression.span())
+                        .message("positional arguments cannot follow named arguments")
+                        .build()
+                        .unwrap();
+
+                    parser.errors.push(error)
+                }
+                Argument::Positional(_) => (),
+            }
+
+            argument
+        }));
+
+        Call {
+            span: operand.span().union(closing.span()),
+            operand: Box::new(operand),
+            arguments,
+        }
+        .into()
+    }
+
+    /// Parse a single argument in a call. An argument that starts with an identifier and a `:` is always a named
+    /// argument, so `f(x: Int)` passes `Int` as the argument `x`. An ascription has to be parenthesized to be passed
+    /// positionally instead, as in `f((x: Int))`.
+    fn parse_argument(&mut self) -> Argument {
+        let name = match (self.peek(), self.peek_nth(1).token()) {
+            (Ok(name), Ok(Token::Colon)) if name.kind() == Token::Identifier => name,
+            _ => return Argument::Positional(self.parse_expression()),
+        };
+
+        // We've already peeked the name and the colon, so we can skip over them.
+        let _ = self.next();
+        let _ = self.next();
+
+        let expression = self.parse_expression();
+
+        // SAFETY: We've checked that the token is an identifier above, so the unwrap will not fail.
+        let name: Identifer = name.try_into().unwrap();
+
+        Argument::Named(NamedArgument {
+            span: name.span().union(expression.span()),
+            name,
+            expression: Box::new(expression),
+        })
+    }
+
+    pub fn parse_pattern(&mut self) -> Pattern {
+        let first = self.parse_pattern_alternative();
+        if self.peek_matches(Token::Pipe).is_err() {
+            return first;
+        }
+
+        let mut alternatives = vec![first];
+        while self.peek_matches(Token::Pipe).is_ok() {
+            let _ = self.next();
+            alternatives.push(self.parse_pattern_alternative());
+        }
+
+        // SAFETY: There are always at least two alternatives here, so the unwraps will not fail.
+        OrPattern {
+            span: alternatives
+                .first()
+                .unwrap()
+                .span()
+                .union(alternatives.last().unwrap().span()),
+            alternatives,
+        }
+        .into()
+    }
+
+    /// Parse a single pattern, without any `|` alternatives.
+    fn parse_pattern_alternative(&mut self) -> Pattern {
+        let token = bail!(self => self.expect_matches(BEGINS_PATTERN));
+
+        match token.kind() {
+            Token::Identifier if &self.source()[token.span()] == "_" => {
+                Pattern::Wildcard(token.span())
+            }
+            Token::Identifier if self.peek_matches(Token::At).is_ok() => {
+                self.parse_binding_pattern(token)
+            }
+            Token::Identifier => self.parse_path_pattern(token),
+            Token::OpeningParen => self.parse_tuple_pattern(token),
+            token_category![Literal] | Token::Minus => {
+                let start = bail!(self => self.parse_literal_pattern(token));
+                match self.peek().token() {
+                    Ok(Token::DotDot | Token::DotDotEquals) => {
+                        let operator = bail!(self => self.next());
+                        self.parse_range_pattern(Some(start), operator)
+                    }
+                    _ => Pattern::Literal(start),
+                }
+            }
+            // A `..` on its own is a rest pattern, but when it's followed by a literal, it's a range with no start.
+            Token::DotDot if self.peek_matches(BEGINS_LITERAL_PATTERN).is_err() => {
+                Pattern::Rest(token.span())
+            }
+            Token::DotDot | Token::DotDotEquals => self.parse_range_pattern(None, token),
+            _ => unreachable!(),
+        }
+    }
+
+    /// Parse a literal pattern that begins with `token`, which is either the literal itself or a `-` before a number.
+    fn parse_literal_pattern(&mut self, token: SpannedToken) -> Result<LiteralPattern, Error> {
+        let (negative, literal) = match token.kind() {
+            // Only numbers can be negated. If something else is, it's skipped along with the `-`, but nothing after it.
+            Token::Minus => {
+                if let Err(error) = self.peek_matches(NUMBERS) {
+                    let _ = self.next();
+                    return Err(error);
+                }
+
+                (true, self.next()?)
+            }
+            _ => (false, token),
+        };
+
+        // SAFETY: The token is guaranteed to be a literal, so the unwrap will not fail.
+        Ok(LiteralPattern {
+            span: token.span().union(literal.span()),
+            negative,
+            literal: literal.try_into().unwrap(),
+        })
+    }
+
+    fn parse_binding_pattern(&mut self, name: SpannedToken) -> Pattern {
+        let _ = self.next();
+        let pattern = self.parse_pattern_alternative();
+
+        BindingPattern {
+            span: name.span().union(pattern.span()),
+            name: WordPattern { span: name.span() },
+            pattern: Box::new(pattern),
+        }
+        .into()
+    }
+
+    fn parse_path_pattern(&mut self, first: SpannedToken) -> Pattern {
+        let path = self.parse_path(first);
+
+        match self.peek().token() {
+            Ok(Token::OpeningParen) => self.parse_constructor_pattern(path),
+            // Much like struct literals, struct patterns aren't allowed where a `{` could begin a block instead.
+            Ok(Token::OpeningBrace) if self.allow_struct_literals => {
+                self.parse_struct_pattern(path)
+            }
+            _ if path.segments.len() == 1 => Pattern::Word(WordPattern { span: path.span }),
+            _ => Pattern::Path(path),
+        }
+    }
+
+    fn parse_constructor_pattern(&mut self, path: Path) -> Pattern {
+        let token = bail!(self => self.next());
+        let (fields, closing) =
+            bail!(self => self.parse_separated(token, Token::ClosingParen, Self::parse_pattern));
+
+        ConstructorPattern {
+            span: path.span.union(closing.span()),
+            path,
+            fields,
+        }
+        .into()
+    }
+
+    fn parse_struct_pattern(&mut self, path: Path) -> Pattern {
+        let token = bail!(self => self.next());
+        let mut rest = None;
+
+        let (fields, closing) = bail!(self => self.parse_separated(token, Token::ClosingBrace, |parser| {
+            let next = bail!(parser => parser.peek());
+            if let Some(rest) = rest {
+                let error = parser
+                    .error()
+                    .location(rest)
+                    .message("`..` must come last in a struct pattern")
+                    .build()
+                    .unwrap();
+
+                parser.errors.push(error);
+            }
+
+            if next.kind() == Token::DotDot {
+                let _ = parser.next();
+                rest = Some(next.span());
+                return None;
+            }
+
+            parser.parse_field_pattern()
+        }));
+
+        StructPattern {
+            span: path.span.union(closing.span()),
+            path,
+            fields: fields.into_iter().flatten().collect(),
+            rest,
+        }
+        .into()
+    }
+
+    fn parse_field_pattern(&mut self) -> Option<FieldPattern> {
+        let name = bail!(self => self.expect_matches(Token::Identifier));
+
+        // A field without a pattern binds to its own name.
+        let pattern = match self.peek_matches(Token::Colon) {
+            Ok(_) => {
+                let _ = self.next();
+                self.parse_pattern()
+            }
+            Err(_) => Pattern::Word(WordPattern { span: name.span() }),
+        };
+
+        // SAFETY: The name is guaranteed to be an identifier, so the unwrap will not fail.
+        Some(FieldPattern {
+            span: name.span().union(pattern.span()),
+            name: name.try_into().unwrap(),
+            pattern,
+        })
+    }
+
+    fn parse_tuple_pattern(&mut self, opening: SpannedToken) -> Pattern {
+        let (mut elements, trailing_comma, closing) = bail!(self => self.parse_separated_with_trailing_comma(
+            opening,
+            Token::ClosingParen,
+            Self::parse_pattern,
+        ));
+
+        // Without a trailing comma, a single pattern in parentheses is just that pattern, not a tuple.
+        if elements.len() == 1 && !trailing_comma {
+            return elements.remove(0);
+        }
+
+        TuplePattern {
+            span: opening.span().union(closing.span()),
+            elements,
+        }
+        .into()
+    }
+
+    /// Parse a range pattern. The start of the range (if there is one) and the `..` or `..=` are expected to have
+    /// already been consumed.
+    fn parse_range_pattern(
+        &mut self,
+        start: Option<LiteralPattern>,
+        operator: SpannedToken,
+    ) -> Pattern {
+        let inclusive = operator.kind() == Token::DotDotEquals;
+
+        // An inclusive range needs an end, but an exclusive one can be left open, as in `1..`.
+        let end = match self.peek_matches(BEGINS_LITERAL_PATTERN) {
+            Ok(_) => {
+                let token = bail!(self => self.next());
+                Some(bail!(self => self.parse_literal_pattern(token)))
+            }
+            Err(_) if inclusive => {
+                let token = bail!(self => self.expect_matches(BEGINS_LITERAL_PATTERN));
+                Some(bail!(self => self.parse_literal_pattern(token)))
+            }
+            Err(_) => None,
+        };
+
+        let start_span = start.as_ref().map_or(operator.span(), Spanned::span);
+        let end_span = end.as_ref().map_or(operator.span(), Spanned::span);
+
+        RangePattern {
+            span: start_span.union(end_span),
+            start,
+            end,
+            inclusive,
+        }
+        .into()
+    }
+
+    pub fn parse_type(&mut self) -> TypeExpr {
+        self.parse_type_with(true)
+    }
+
+    /// Parse a type that follows an expression, as in a cast or an ascription. Here, `<` is a comparison, so
+    /// `x as Int < y` compares `x as Int` with `y`, and generic arguments have to be written in square brackets.
+    fn parse_expression_type(&mut self) -> TypeExpr {
+        self.parse_type_with(false)
+    }
+
+    /// Parse a type, where `angle_brackets` says whether generic arguments can be written in angle brackets. This only
+    /// applies to the outermost type, as `<` can't be mistaken for a comparison inside of delimiters.
+    fn parse_type_with(&mut self, angle_brackets: bool) -> TypeExpr {
+        // If we find something that could follow a type instead, we assume the type was left out entirely, and leave
+        // that token for whoever asked for the type.
+        let token = bail!(self => self.expect_matches_or_recover_with(BEGINS_TYPE, FOLLOWS_TYPE));
+        if !BEGINS_TYPE.contains(&token.kind()) {
+            return TypeExpr::error(token.span());
+        }
+
+        match token.kind() {
+            Token::Identifier if &self.source()[token.span()] == "_" => {
+                TypeExpr::Hole(token.span())
+            }
+            Token::Identifier => self.parse_path_type(token, angle_brackets),
+            Token::OpeningParen => self.parse_tuple_type(token),
+            Token::OpeningBracket => self.parse_array_type(token),
+            Token::Function => self.parse_function_type(token, angle_brackets),
+            _ => unreachable!(),
+        }
+    }
+
+    fn parse_path_type(&mut self, first: SpannedToken, angle_brackets: bool) -> TypeExpr {
+        let base = self.parse_path(first);
+
+        // Generic arguments are usually written in square brackets, but angle brackets are accepted too, as long as they
+        // can't be confused with a comparison.
+        let closing = match self.peek().token() {
+            Ok(Token::OpeningBracket) => Token::ClosingBracket,
+            Ok(Token::Lesser) if angle_brackets => Token::Greater,
+            _ => return base.into(),
+        };
+
+        let opening = bail!(self => self.next());
+        let (arguments, closing) =
+            bail!(self => self.parse_separated(opening, closing, Self::parse_type));
+
+        GenericType {
+            span: base.span().union(closing.span()),
+            base,
+            arguments,
+        }
+        .into()
+    }
+
+    fn parse_tuple_type(&mut self, opening: SpannedToken) -> TypeExpr {
+        let (mut elements, trailing_comma, closing) = bail!(self => self.parse_separated_with_trailing_comma(
+            opening,
+            Token::ClosingParen,
+            Self::parse_type,
+        ));
+
+        // Without a trailing comma, a single type in parentheses is just that type, not a tuple.
+        if elements.len() == 1 && !trailing_comma {
+            return elements.remove(0);
+        }
+
+        TupleType {
+            span: opening.span().union(closing.span()),
+            elements,
+        }
+        .into()
+    }
+
+    fn parse_array_type(&mut self, opening: SpannedToken) -> TypeExpr {
+        self.unclosed_delimiters.push(opening);
+
+        let element = self.parse_type();
+        let length = match self.peek_matches(Token::Semicolon) {
+            Ok(_) => {
+                let _ = self.next();
+                Some(Box::new(
+                    self.with_struct_literals(true, Self::parse_expression),
+                ))
+            }
+            Err(_) => None,
+        };
+
+        let closing = bail!(self => self.expect_matches(Token::ClosingBracket));
+        self.unclosed_delimiters.pop();
+
+        ArrayType {
+            span: opening.span().union(closing.span()),
+            element: Box::new(element),
+            length,
+        }
+        .into()
+    }
+
+    fn parse_function_type(&mut self, keyword: SpannedToken, angle_brackets: bool) -> TypeExpr {
+        let opening = bail!(self => self.expect_matches(Token::OpeningParen));
+        let (parameters, closing) =
+            bail!(self => self.parse_separated(opening, Token::ClosingParen, Self::parse_type));
+
+        let return_type = match self.peek_matches(Token::Arrow) {
+            Ok(_) => {
+                let _ = self.next();
+                Some(Box::new(self.parse_type_with(angle_brackets)))
+            }
+            Err(_) => None,
+        };
+
+        let end = return_type
+            .as_ref()
+            .map_or(closing.span(), |return_type| return_type.span());
+
+        FunctionType {
+            span: keyword.span().union(end),
+            parameters,
+            return_type,
+        }
+        .into()
+    }
+
+    /// Parse a path made up only of `::`-separated names. The first name is expected to have already been consumed.
+    fn parse_path(&mut self, first: SpannedToken) -> Path {
+        let mut span = first.span();
+        // SAFETY: Every segment of a path is guaranteed to be an identifier, so the unwraps will not fail.
+        let mut segments = vec![first.try_into().unwrap()];
+
+        while self.peek_matches(Token::ColonColon).is_ok() {
+            let _ = self.next();
+            match self.expect_matches(Token::Identifier) {
+                Ok(token) => {
+                    span = span.union(token.span());
+                    segments.push(token.try_into().unwrap());
+                }
+                Err(error) => {
+                    self.errors.push(error);
+                    break;
+                }
+            }
+        }
+
+        Path { span, segments }
+    }
+
+    /// Parse any inner attributes at the start of a module.
+    fn parse_inner_attributes(&mut self) -> Vec<Attribute> {
+        let mut attributes = Vec::new();
+
+        while let (Ok(Token::Hash), Ok(Token::Exclamation)) | (Ok(Token::InnerDocComment), _) =
+            (self.peek().token(), self.peek_nth(1).token())
+        {
+            attributes.extend(self.parse_attribute());
+        }
+
+        attributes
+    }
+
+    /// Parse any outer attributes. Inner attributes are reported and discarded, since they're only allowed at the start
+    /// of a module.
+    fn parse_outer_attributes(&mut self) -> Vec<Attribute> {
+        let mut attributes = Vec::new();
+
+        while self.peek_matches(BEGINS_ATTRIBUTE).is_ok() {
+            let attribute = match self.parse_attribute() {
+                Some(attribute) => attribute,
+                None => continue,
+            };
+
+            if attribute.style == AttributeStyle::Inner {
+                let error = self
+                    .error()
+                    .location(attribute.span)
+                    .message("inner attributes are only allowed at the start of a module")
+                    .build()
+                    .unwrap();
+
+                self.errors.push(error);
+                continue;
+            }
+
+            attributes.push(attribute);
+        }
+
+        attributes
+    }
+
+    fn parse_attribute(&mut self) -> Option<Attribute> {
+        let hash = bail!(self => self.expect_matches(BEGINS_ATTRIBUTE));
+
+        // Doc comments are just another way of writing an attribute.
+        let style = match hash.kind() {
+            Token::OuterDocComment => Some(AttributeStyle::Outer),
+            Token::InnerDocComment => Some(AttributeStyle::Inner),
+            _ => None,
+        };
+
+        if let Some(style) = style {
+            // Both `///` and `//!` are three bytes long.
+            let text = Span::new(hash.span().start() + 3, hash.span().end());
+            return Some(Attribute {
+                span: hash.span(),
+                style,
+                kind: AttributeKind::Doc(text),
+            });
+        }
+
+        let style = match self.peek_matches(Token::Exclamation) {
+            Ok(_) => {
+                let _ = self.next();
+                AttributeStyle::Inner
+            }
+            Err(_) => AttributeStyle::Outer,
+        };
+
+        let token = bail!(self => self.expect_matches(Token::OpeningBracket));
+        self.unclosed_delimiters.push(token);
+
+        let first = bail!(self => self.expect_matches(Token::Identifier));
+        let path = self.parse_path(first);
+
+        let mut arguments = Vec::new();
+        let closing = loop {
+            let next = bail!(self => self.peek());
+            if next.kind() == Token::ClosingBracket {
+                break bail!(self => self.next());
+            }
+
+            arguments.push(self.parse_token_tree()?);
+        };
+
+        self.unclosed_delimiters.pop();
+
+        Some(Attribute {
+            span: hash.span().union(closing.span()),
+            style,
+            kind: AttributeKind::Normal { path, arguments },
+        })
+    }
+
+    fn parse_token_tree(&mut self) -> Option<TokenTree> {
+        let open = bail!(self => self.next());
+        let closing = match open.kind() {
+            Token::OpeningParen => Token::ClosingParen,
+            Token::OpeningBracket => Token::ClosingBracket,
+            Token::OpeningBrace => Token::ClosingBrace,
+            _ => return Some(TokenTree::Token(open)),
+        };
+
+        self.unclosed_delimiters.push(open);
+
+        let mut trees = Vec::new();
+        let close = loop {
+            let next = bail!(self => self.peek());
+            match next.kind() {
+                kind if kind == closing => break bail!(self => self.next()),
+                // A closing delimiter that doesn't match the opening one is reported, and closes the tree anyway. If it
+                // matches a delimiter that's still open further out, it's left for that one to close, so that
+                // something like `#[a(b] fn f() {}` doesn't take the item with it.
+                token_category![ClosingBracket] => {
+                    let error = self
+                        .error()
+                        .location(next.span())
+                        .mismatch(closing, next)
+                        .build()
+                        .unwrap();
+
+                    self.errors.push(error);
+                    if !self.closes_unclosed_delimiter(next.kind()) {
+                        let _ = self.next();
+                    }
+
+                    break next;
+                }
+                _ => trees.push(self.parse_token_tree()?),
+            }
+        };
+
+        self.unclosed_delimiters.pop();
+
+        Some(TokenTree::Delimited(DelimitedTree {
+            span: open.span().union(close.span()),
+            open,
+            trees,
+            close,
+        }))
+    }
+
+    /// Check whether `closing` is the closing delimiter for any delimiter that's still open.
+    fn closes_unclosed_delimiter(&self, closing: Token) -> bool {
+        self.unclosed_delimiters.iter().any(|open| {
+            matches!(
+                (open.kind(), closing),
+                (Token::OpeningParen, Token::ClosingParen)
+                    | (Token::OpeningBracket, Token::ClosingBracket)
+                    | (Token::OpeningBrace, Token::ClosingBrace)
+            )
+        })
+    }
+
+    /// Report attributes that aren't followed by anything they could be applied to.
+    fn report_dangling_attributes(&mut self, attributes: &[Attribute], expected: &str) {
+        for attribute in attributes {
+            let error = self
+                .error()
+                .location(attribute.span)
+                .message(format!("expected {} after this attribute", expected))
+                .build()
+                .unwrap();
+
+            self.errors.push(error)
+        }
+    }
+
+    /// Parse a comma-separated list of elements using `parse_element`, up to and including the closing delimiter. A
+    /// trailing comma is allowed. The opening delimiter is expected to have already been consumed, and is considered
+    /// unclosed until the closing delimiter is found.
+    fn parse_separated<T>(
+        &mut self,
+        opening: SpannedToken,
+        closing: Token,
+        parse_element: impl FnMut(&mut Self) -> T,
+    ) -> Result<(Vec<T>, SpannedToken), Error> {
+        self.parse_separated_with_trailing_comma(opening, closing, parse_element)
+            .map(|(elements, _, closing)| (elements, closing))
+    }
+
+    /// Like [Parser::parse_separated], but also reports whether the list ended with a trailing comma.
+    fn parse_separated_with_trailing_comma<T>(
+        &mut self,
+        opening: SpannedToken,
+        closing: Token,
+        mut parse_element: impl FnMut(&mut Self) -> T,
+    ) -> Result<(Vec<T>, bool, SpannedToken), Error> {
+        self.unclosed_delimiters.push(opening);
+
+        let separators = [Token::Comma, closing];
+        let mut elements = Vec::new();
+        let mut trailing_comma = false;
+
+        let closing = loop {
+            if self.peek()?.kind() == closing {
+                break self.next()?;
+            }
+
+            elements.push(self.with_struct_literals(true, &mut parse_element));
+
+            let separator = self.expect_matches(&separators[..])?;
+            if separator.kind() == closing {
+                trailing_comma = false;
+                break separator;
+            }
+
+            trailing_comma = true;
+        };
+
+        self.unclosed_delimiters.pop();
+        Ok((elements, trailing_comma, closing))
+    }
+
+    fn add_leftover_errors(&mut self) {
+        if let Ok(token) = self.peek() {
+            let leftover = Span::new(token.span().start(), self.source().len());
+            let error = self
+                .error()
+                .location(leftover)
+                .unexpected(Some(token.kind()))
+                .build()
+                .unwrap();
+
+            self.errors.push(error)
+        }
+    }
+
+    fn add_delimiter_errors(&mut self) {
+        for unclosed in self.unclosed_delimiters.clone() {
+            let error = self
+                .error()
+                .location(unclosed.span())
+                .message("unclosed delimiter")
+                .build()
+                .unwrap();
+
+            self.errors.push(error)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        visitor::Visitor, AssignOperator, BinaryOperator, LiteralExpression, PathAccess, Statement,
+    };
+
+    /// Unwrap a single-field enum variant, or panic with whatever was found instead.
+    macro_rules! variant {
+        ($value:expr, $variant:path) => {
+            match $value {
+                $variant(inner) => inner,
+                other => panic!("expected {}, found {:?}", stringify!($variant), other),
+            }
+        };
+    }
+
+    fn parse(source: &str) -> Expression {
+        let mut parser = Parser::new(source);
+        let expression = parser.parse();
+        assert_no_errors(source, &parser);
+        expression
+    }
+
+    fn parse_module(source: &str) -> Module {
+        let mut parser = Parser::new(source);
+        let module = parser.parse_module();
+        assert_no_errors(source, &parser);
+        module
+    }
+
+    fn parse_type(source: &str) -> TypeExpr {
+        let mut parser = Parser::new(source);
+        let ty = parser.parse_type();
+        assert_no_errors(source, &parser);
+        ty
+    }
+
+    fn parse_pattern(source: &str) -> Pattern {
+        let mut parser = Parser::new(source);
+        let pattern = parser.parse_pattern();
+        assert_no_errors(source, &parser);
+        pattern
+    }
+
+    fn assert_no_errors(source: &str, parser: &Parser) {
+        let errors = messages(source, parser);
+        assert!(errors.is_empty(), "{:?}", errors);
+    }
+
+    /// Run `parse` over `source`, producing the source text and message of every error it reports.
+    fn errors<'a, T>(
+        source: &'a str,
+        parse: impl FnOnce(&mut Parser<'a>) -> T,
+    ) -> Vec<(&'a str, String)> {
+        let mut parser = Parser::new(source);
+        parse(&mut parser);
+        messages(source, &parser)
+    }
+
+    fn messages<'a>(source: &'a str, parser: &Parser) -> Vec<(&'a str, String)> {
+        parser
+            .errors()
+            .iter()
+            .map(|error| (&source[error.span()], error.details().to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_calls() {
+        let source = "f(a, b, name: c,)";
+        let call = variant!(parse(source), Expression::Call);
+        assert_eq!(&source[call.operand.span()], "f");
+        assert_eq!(call.arguments.len(), 3);
+        assert!(matches!(call.arguments[0], Argument::Positional(_)));
+        assert!(matches!(call.arguments[1], Argument::Positional(_)));
+
+        let named = variant!(&call.arguments[2], Argument::Named);
+        assert_eq!(&source[named.name.span()], "name");
+        assert_eq!(&source[named.span], "name: c");
+    }
+
+    #[test]
+    fn test_named_arguments_take_precedence_over_ascriptions() {
+        let source = "f(x: Int)";
+        let call = variant!(parse(source), Expression::Call);
+        let named = variant!(&call.arguments[0], Argument::Named);
+        assert_eq!(&source[named.name.span()], "x");
+        assert_eq!(&source[named.expression.span()], "Int");
+
+        let call = variant!(parse("f((x: Int))"), Expression::Call);
+        let positional = variant!(&call.arguments[0], Argument::Positional);
+        variant!(positional, Expression::Ascription);
+    }
+
+    #[test]
+    fn test_calls_are_postfix() {
+        let call = variant!(parse("f(a)(b)"), Expression::Call);
+        variant!(*call.operand, Expression::Call);
+
+        let call = variant!(parse("(f)()"), Expression::Call);
+        assert!(call.arguments.is_empty());
+    }
+
+    #[test]
+    fn test_positional_after_named_argument() {
+        assert_eq!(
+            errors("f(name: a, b)", Parser::parse),
+            [(
+                "b",
+                "positional arguments cannot follow named arguments".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_is_expressions() {
+        let source = "value is None";
+        let is = variant!(parse(source), Expression::Is);
+        assert_eq!(&source[is.expression.span()], "value");
+        variant!(is.pattern, Pattern::Word);
+    }
+
+    #[test]
+    fn test_is_binds_tighter_than_and() {
+        let source = "a and b is Some(x)";
+        let and = variant!(parse(source), Expression::Binary);
+        assert!(matches!(and.operator.operator(), BinaryOperator::And));
+
+        let is = variant!(*and.right, Expression::Is);
+        assert_eq!(&source[is.expression.span()], "b");
+        variant!(is.pattern, Pattern::Constructor);
+    }
+
+    #[test]
+    fn test_blocks() {
+        let source = "{ a; b;; c }";
+        let block = variant!(parse(source), Expression::Block);
+        assert_eq!(block.statements.len(), 2);
+        assert_eq!(&source[block.tail.unwrap().span()], "c");
+        assert_eq!(&source[block.span], source);
+
+        let block = variant!(parse("{ a; }"), Expression::Block);
+        assert_eq!(block.statements.len(), 1);
+        assert!(block.tail.is_none());
+    }
+
+    #[test]
+    fn test_block_like_statements() {
+        let source = "{ if a { b } else { c } (d, e) }";
+        let block = variant!(parse(source), Expression::Block);
+        variant!(&block.statements[0], Statement::Expression);
+        variant!(*block.tail.unwrap(), Expression::Tuple);
+
+        let block = variant!(parse("{ while a { b } -x }"), Expression::Block);
+        assert_eq!(block.statements.len(), 1);
+        variant!(*block.tail.unwrap(), Expression::Unary);
+
+        let block = variant!(parse("{ loop {} [1, 2] }"), Expression::Block);
+        assert_eq!(block.statements.len(), 1);
+        variant!(*block.tail.unwrap(), Expression::Array);
+
+        // A block-like expression at the end of a block is still its tail.
+        let block = variant!(parse("{ a; if b { c } }"), Expression::Block);
+        variant!(*block.tail.unwrap(), Expression::If);
+    }
+
+    #[test]
+    fn test_unclosed_blocks() {
+        assert_eq!(
+            errors("{ a; b", Parser::parse),
+            [
+                ("", "unexpected EOF".to_string()),
+                ("{", "unclosed delimiter".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_let_statements() {
+        let source = "{ let (a, b): (Int, Int) = c; a }";
+        let block = variant!(parse(source), Expression::Block);
+        let statement = variant!(&block.statements[0], Statement::Let);
+        variant!(&statement.pattern, Pattern::Tuple);
+        variant!(statement.annotation.as_ref().unwrap(), TypeExpr::Tuple);
+        assert_eq!(&source[statement.value.span()], "c");
+        assert_eq!(&source[statement.span], "let (a, b): (Int, Int) = c;");
+
+        let module = parse_module("let x = 1;");
+        variant!(&module.items[0], Item::Let);
+    }
+
+    #[test]
+    fn test_let_recovery() {
+        assert_eq!(
+            errors("{ let x; }", Parser::parse),
+            [(";", "missing initializer in `let` binding".to_string())]
+        );
+
+        let mut parser = Parser::new("{ let x 1; }");
+        let block = variant!(parser.parse(), Expression::Block);
+        let statement = variant!(&block.statements[0], Statement::Let);
+        variant!(&*statement.value, Expression::Literal);
+        assert_eq!(parser.errors().len(), 1);
+    }
+
+    #[test]
+    fn test_if_expressions() {
+        let source = "if a { 1 } else if b { 2 } else { 3 }";
+        let expression = variant!(parse(source), Expression::If);
+        assert_eq!(&source[expression.condition.span()], "a");
+
+        let else_if = variant!(*expression.else_branch.unwrap(), Expression::If);
+        assert_eq!(&source[else_if.condition.span()], "b");
+        variant!(*else_if.else_branch.unwrap(), Expression::Block);
+    }
+
+    #[test]
+    fn test_if_conditions_with_patterns() {
+        let source = "if x is Some(y) and y > 1 { y }";
+        let expression = variant!(parse(source), Expression::If);
+        let patterns = expression.condition_patterns();
+        assert_eq!(patterns.len(), 1);
+        assert_eq!(&source[patterns[0].span()], "Some(y)");
+    }
+
+    #[test]
+    fn test_if_conditions_are_not_struct_literals() {
+        let source = "if x { }";
+        let expression = variant!(parse(source), Expression::If);
+        variant!(*expression.condition, Expression::Identifier);
+    }
+
+    #[test]
+    fn test_match_expressions() {
+        let source = "match x { A if a > 1 => 1, _ => 2, }";
+        let expression = variant!(parse(source), Expression::Match);
+        assert_eq!(&source[expression.scrutinee.span()], "x");
+        assert_eq!(expression.arms.len(), 2);
+        assert_eq!(&source[expression.arms[0].span], "A if a > 1 => 1");
+        assert_eq!(
+            &source[expression.arms[0].guard.as_ref().unwrap().span()],
+            "a > 1"
+        );
+        variant!(&expression.arms[1].pattern, Pattern::Wildcard);
+    }
+
+    #[test]
+    fn test_block_bodied_match_arms() {
+        let source = "match x { A => {} (a, b) => {} }";
+        let expression = variant!(parse(source), Expression::Match);
+        assert_eq!(expression.arms.len(), 2);
+        variant!(&expression.arms[1].pattern, Pattern::Tuple);
+
+        let source = "match x { 0 => {} ..=5 => {} }";
+        let expression = variant!(parse(source), Expression::Match);
+        assert_eq!(expression.arms.len(), 2);
+        variant!(&expression.arms[1].pattern, Pattern::Range);
+    }
+
+    #[test]
+    fn test_match_arm_recovery() {
+        let mut parser = Parser::new("match x { A => 1, => 2, B => 3 }");
+        let expression = variant!(parser.parse(), Expression::Match);
+        assert_eq!(expression.arms.len(), 2);
+        assert_eq!(parser.errors().len(), 1);
+    }
+
+    #[test]
+    fn test_paths() {
+        let source = "a::b::c";
+        let path = variant!(parse(source), Expression::Path);
+        assert_eq!(&source[path.root.span()], "a");
+        assert_eq!(path.segments.len(), 2);
+        assert!(matches!(path.segments[0].access, PathAccess::Scope));
+        assert_eq!(&source[path.segments[0].span], "::b");
+        assert_eq!(&source[path.segments[1].name.span()], "c");
+    }
+
+    #[test]
+    fn test_member_access() {
+        let source = "x.field.method(1)";
+        let call = variant!(parse(source), Expression::Call);
+        let path = variant!(*call.operand, Expression::Path);
+        assert!(matches!(path.segments[0].access, PathAccess::Member));
+        assert_eq!(&source[path.segments[0].name.span()], "field");
+        assert_eq!(&source[path.segments[1].name.span()], "method");
+    }
+
+    #[test]
+    fn test_modules() {
+        let module = parse_module("fn f() {} struct S; module m { } let x = 1;");
+        assert_eq!(module.items.len(), 4);
+        variant!(&module.items[0], Item::Function);
+        variant!(&module.items[1], Item::Struct);
+        variant!(&module.items[2], Item::Module);
+        variant!(&module.items[3], Item::Let);
+    }
+
+    #[test]
+    fn test_leftover_input() {
+        let errors = errors("1 2 3", Parser::parse);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].0, "2 3");
+    }
+
+    #[test]
+    fn test_item_recovery() {
+        let mut parser = Parser::new("fn f() {} garbage here fn g() {} struct S;");
+        let module = parser.parse_module();
+        assert_eq!(module.items.len(), 3);
+        assert_eq!(parser.errors().len(), 1);
+
+        // Skipping stops at the end of the enclosing module, rather than taking the next item with it.
+        let mut parser = Parser::new("module a { 1 } fn f() {}");
+        let module = parser.parse_module();
+        let inner = variant!(&module.items[0], Item::Module);
+        assert_eq!(&parser.source()[inner.name.span()], "a");
+        variant!(&inner.body.as_ref().unwrap().items[0], Item::Error);
+        let function = variant!(&module.items[1], Item::Function);
+        assert_eq!(&parser.source()[function.signature.name.span()], "f");
+        assert_eq!(parser.errors().len(), 1);
+    }
+
+    #[test]
+    fn test_functions() {
+        let source = "fn add(a: Int, b: Int = 1) -> Int { a + b }";
+        let module = parse_module(source);
+        let function = variant!(&module.items[0], Item::Function);
+        let signature = &function.signature;
+        assert_eq!(&source[signature.name.span()], "add");
+        assert_eq!(&source[signature.span], "fn add(a: Int, b: Int = 1) -> Int");
+        assert_eq!(signature.parameters.len(), 2);
+        assert!(signature.parameters[0].default.is_none());
+        assert_eq!(&source[signature.parameters[1].span], "b: Int = 1");
+        assert_eq!(
+            &source[signature.parameters[1].default.as_ref().unwrap().span()],
+            "1"
+        );
+        assert_eq!(
+            &source[signature.return_type.as_ref().unwrap().span()],
+            "Int"
+        );
+        assert_eq!(&source[function.body.span()], "{ a + b }");
+    }
+
+    #[test]
+    fn test_missing_parameter_comma() {
+        let errors = errors("fn f(a: Int b: Int) {}", Parser::parse_module);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].0, "b: Int)");
+    }
+
+    #[test]
+    fn test_struct_items() {
+        let module = parse_module("struct P { x: Int, y: Int } struct T(Int, Int); struct U;");
+        let named = variant!(&module.items[0], Item::Struct);
+        assert_eq!(variant!(&named.fields, StructFields::Named).len(), 2);
+        let tuple = variant!(&module.items[1], Item::Struct);
+        assert_eq!(variant!(&tuple.fields, StructFields::Tuple).len(), 2);
+        let unit = variant!(&module.items[2], Item::Struct);
+        assert!(matches!(unit.fields, StructFields::Unit));
+    }
+
+    #[test]
+    fn test_struct_literals() {
+        let source = "Point { x: 1, y }";
+        let literal = variant!(parse(source), Expression::Struct);
+        assert_eq!(&source[literal.name.span()], "Point");
+        assert_eq!(literal.fields.len(), 2);
+        assert!(literal.fields[0].value.is_some());
+        assert!(literal.fields[1].value.is_none());
+
+        let source = "match p { }";
+        let expression = variant!(parse(source), Expression::Match);
+        variant!(*expression.scrutinee, Expression::Identifier);
+    }
+
+    #[test]
+    fn test_struct_literal_paths_are_visited() {
+        let literal = variant!(parse("shapes::Point { x: a }"), Expression::Struct);
+        let mut visited = Vec::new();
+        literal.visit(&mut |expression: &Expression| visited.push(expression.clone()));
+        assert!(visited
+            .iter()
+            .any(|expression| matches!(expression, Expression::Path(_))));
+    }
+
+    #[test]
+    fn test_module_items() {
+        let source = "module a { module b; fn f() {} }";
+        let module = parse_module(source);
+        let inline = variant!(&module.items[0], Item::Module);
+        assert_eq!(&source[inline.name.span()], "a");
+
+        let body = inline.body.as_ref().unwrap();
+        assert_eq!(body.items.len(), 2);
+        let declaration = variant!(&body.items[0], Item::Module);
+        assert!(declaration.body.is_none());
+        assert_eq!(&source[declaration.span], "module b;");
+    }
+
+    #[test]
+    fn test_attributes() {
+        let source = "#![feature(x)]\n#[derive(Debug, Clone)] struct S { #[a] x: Int }";
+        let module = parse_module(source);
+        assert_eq!(module.attributes.len(), 1);
+        assert_eq!(module.attributes[0].style, AttributeStyle::Inner);
+
+        let item = variant!(&module.items[0], Item::Struct);
+        assert_eq!(item.attributes.len(), 1);
+        assert_eq!(item.attributes[0].style, AttributeStyle::Outer);
+        match &item.attributes[0].kind {
+            AttributeKind::Normal { path, arguments } => {
+                assert_eq!(&source[path.span], "derive");
+                assert_eq!(arguments.len(), 1);
+            }
+            kind => panic!("expected a normal attribute, found {:?}", kind),
+        }
+
+        let fields = variant!(&item.fields, StructFields::Named);
+        assert_eq!(fields[0].attributes.len(), 1);
+    }
+
+    #[test]
+    fn test_statement_attributes() {
+        let block = variant!(parse("{ #[a] let x = 1; #[b] f(); x }"), Expression::Block);
+        let statement = variant!(&block.statements[0], Statement::Let);
+        assert_eq!(statement.attributes.len(), 1);
+        let statement = variant!(&block.statements[1], Statement::Expression);
+        assert_eq!(statement.attributes.len(), 1);
+    }
+
+    #[test]
+    fn test_misplaced_attributes() {
+        assert_eq!(
+            errors("{ #[a] x }", Parser::parse),
+            [(
+                "#[a]",
+                "attributes are not allowed on the tail expression of a block".to_string()
+            )]
+        );
+
+        assert_eq!(
+            errors("{ #[a] }", Parser::parse),
+            [(
+                "#[a]",
+                "expected a statement after this attribute".to_string()
+            )]
+        );
+
+        assert_eq!(
+            errors("#[a] 1", Parser::parse),
+            [("#[a]", "attributes are not allowed here".to_string())]
+        );
+
+        let errors = errors("fn f() {} #![late]", Parser::parse_module);
+        assert_eq!(
+            errors[0],
+            (
+                "#![late]",
+                "inner attributes are only allowed at the start of a module".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_mismatched_attribute_delimiters() {
+        // The mismatched delimiter closes the argument, so the items after the attribute are still parsed.
+        let source = "#[a(b] fn f() {} fn g() {}";
+        let mut parser = Parser::new(source);
+        let module = parser.parse_module();
+        assert_eq!(module.items.len(), 2);
+        let function = variant!(&module.items[0], Item::Function);
+        assert_eq!(function.attributes.len(), 1);
+        variant!(&module.items[1], Item::Function);
+        assert_eq!(
+            messages(source, &parser),
+            [("]", "expected `)` but found `]`".to_string())]
+        );
+
+        let mut parser = Parser::new("#[a(b]] fn f() {} fn g() {}");
+        let module = parser.parse_module();
+        assert_eq!(module.items.len(), 2);
+        variant!(&module.items[0], Item::Function);
+        variant!(&module.items[1], Item::Function);
+    }
+
+    #[test]
+    fn test_types() {
+        let path = variant!(parse_type("Maybe::Int"), TypeExpr::Path);
+        assert_eq!(path.segments.len(), 2);
+
+        for source in &["List[Int]", "List<Int>"] {
+            let generic = variant!(parse_type(source), TypeExpr::Generic);
+            assert_eq!(&source[generic.base.span], "List");
+            assert_eq!(generic.arguments.len(), 1);
+        }
+
+        assert_eq!(
+            variant!(parse_type("(Int, Float)"), TypeExpr::Tuple)
+                .elements
+                .len(),
+            2
+        );
+        assert_eq!(
+            variant!(parse_type("(Int,)"), TypeExpr::Tuple)
+                .elements
+                .len(),
+            1
+        );
+        assert!(variant!(parse_type("()"), TypeExpr::Tuple)
+            .elements
+            .is_empty());
+        variant!(parse_type("(Int)"), TypeExpr::Path);
+
+        let function = variant!(parse_type("fn(Int) -> Int"), TypeExpr::Function);
+        assert_eq!(function.parameters.len(), 1);
+        assert!(function.return_type.is_some());
+
+        assert!(variant!(parse_type("[Int; 4]"), TypeExpr::Array)
+            .length
+            .is_some());
+        assert!(variant!(parse_type("[Int]"), TypeExpr::Array)
+            .length
+            .is_none());
+        variant!(parse_type("_"), TypeExpr::Hole);
+    }
+
+    #[test]
+    fn test_missing_types() {
+        let mut parser = Parser::new("let x: = 1;");
+        let module = parser.parse_module();
+        let statement = variant!(&module.items[0], Item::Let);
+        variant!(statement.annotation.as_ref().unwrap(), TypeExpr::Error);
+        variant!(&*statement.value, Expression::Literal);
+        assert_eq!(parser.errors().len(), 1);
+    }
+
+    #[test]
+    fn test_generic_parameters() {
+        let source = "fn f[T: A + B = C, ..Ts]() {}";
+        let module = parse_module(source);
+        let function = variant!(&module.items[0], Item::Function);
+        let generics = &function.signature.generics;
+        assert_eq!(generics.len(), 2);
+        assert_eq!(&source[generics[0].span], "T: A + B = C");
+        assert_eq!(generics[0].bounds.len(), 2);
+        assert!(generics[0].default.is_some());
+        assert!(generics[1].variadic);
+        assert_eq!(&source[generics[1].span], "..Ts");
+
+        let module = parse_module("struct S<T> { x: T }");
+        let item = variant!(&module.items[0], Item::Struct);
+        assert_eq!(item.generics.len(), 1);
+    }
+
+    #[test]
+    fn test_variadic_generic_parameters_come_last() {
+        assert_eq!(
+            errors("fn f[..Ts, T]() {}", Parser::parse_module),
+            [(
+                "..Ts",
+                "a variadic generic parameter must come last".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_patterns() {
+        variant!(parse_pattern("1"), Pattern::Literal);
+        variant!(parse_pattern("_"), Pattern::Wildcard);
+        variant!(parse_pattern("x"), Pattern::Word);
+        variant!(parse_pattern("Maybe::None"), Pattern::Path);
+
+        let tuple = variant!(parse_pattern("(a, .., b)"), Pattern::Tuple);
+        variant!(&tuple.elements[1], Pattern::Rest);
+
+        let source = "Maybe::Some(y)";
+        let constructor = variant!(parse_pattern(source), Pattern::Constructor);
+        assert_eq!(&source[constructor.path.span], "Maybe::Some");
+        assert_eq!(constructor.fields.len(), 1);
+
+        let source = "Point { x, y: 0, .. }";
+        let structure = variant!(parse_pattern(source), Pattern::Struct);
+        assert_eq!(structure.fields.len(), 2);
+        assert_eq!(&source[structure.rest.unwrap()], "..");
+
+        let or = variant!(parse_pattern("A | B | C"), Pattern::Or);
+        assert_eq!(or.alternatives.len(), 3);
+
+        let source = "n @ 1..=5";
+        let binding = variant!(parse_pattern(source), Pattern::Binding);
+        assert_eq!(&source[binding.name.span], "n");
+        let range = variant!(*binding.pattern, Pattern::Range);
+        assert!(range.inclusive);
+
+        let range = variant!(parse_pattern("..5"), Pattern::Range);
+        assert!(range.start.is_none());
+    }
+
+    #[test]
+    fn test_negative_literal_patterns() {
+        let literal = variant!(parse_pattern("-1"), Pattern::Literal);
+        assert!(literal.negative);
+        variant!(literal.literal, LiteralExpression::Integer);
+
+        let source = "-2.5..=-1.0";
+        let range = variant!(parse_pattern(source), Pattern::Range);
+        assert_eq!(&source[range.span], source);
+        assert!(range.start.unwrap().negative);
+        let end = range.end.unwrap();
+        assert_eq!(&source[end.span], "-1.0");
+        variant!(end.literal, LiteralExpression::Float);
+
+        let range = variant!(parse_pattern("0..=-1"), Pattern::Range);
+        assert!(!range.start.unwrap().negative);
+        assert!(range.end.unwrap().negative);
+
+        let is = variant!(parse("x is -1"), Expression::Is);
+        variant!(is.pattern, Pattern::Literal);
+
+        let expression = variant!(parse("match x { -1 => 0, _ => 1 }"), Expression::Match);
+        variant!(&expression.arms[0].pattern, Pattern::Literal);
+
+        assert_eq!(
+            errors("-a", Parser::parse_pattern),
+            [(
+                "a",
+                "expected an integer literal or a float literal but found \"a\"".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_pattern_positions() {
+        let source =
+            "fn f((a, b): (Int, Int)) { let Point { x, .. } = p; match q { Some(y) => y } }";
+        let module = parse_module(source);
+        let function = variant!(&module.items[0], Item::Function);
+        variant!(&function.signature.parameters[0].pattern, Pattern::Tuple);
+    }
+
+    #[test]
+    fn test_struct_pattern_rest_comes_last() {
+        assert_eq!(
+            errors("Point { .., x }", Parser::parse_pattern),
+            [("..", "`..` must come last in a struct pattern".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_assignments_are_right_associative() {
+        let source = "a = b = c";
+        let assign = variant!(parse(source), Expression::Assign);
+        assert_eq!(&source[assign.target.span()], "a");
+        let inner = variant!(*assign.value, Expression::Assign);
+        assert_eq!(&source[inner.span], "b = c");
+    }
+
+    #[test]
+    fn test_compound_assignments() {
+        let assign = variant!(parse("x += 1"), Expression::Assign);
+        assert!(matches!(
+            assign.operator.operator(),
+            AssignOperator::AddAssign
+        ));
+
+        let assign = variant!(parse("a.b[0] = 1"), Expression::Assign);
+        variant!(*assign.target, Expression::Index);
+    }
+
+    #[test]
+    fn test_invalid_assignment_targets() {
+        assert_eq!(
+            errors("1 + 2 = x", Parser::parse),
+            [("1 + 2", "invalid assignment target".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_arrays() {
+        let array = variant!(parse("[a, b, c,]"), Expression::Array);
+        assert_eq!(array.elements.len(), 3);
+        assert!(variant!(parse("[]"), Expression::Array).elements.is_empty());
+
+        let source = "[0; n]";
+        let repeat = variant!(parse(source), Expression::ArrayRepeat);
+        assert_eq!(&source[repeat.value.span()], "0");
+        assert_eq!(&source[repeat.count.span()], "n");
+    }
+
+    #[test]
+    fn test_index_expressions() {
+        let source = "xs[i][j]";
+        let index = variant!(parse(source), Expression::Index);
+        assert_eq!(&source[index.index.span()], "j");
+        let inner = variant!(*index.operand, Expression::Index);
+        assert_eq!(&source[inner.span], "xs[i]");
+    }
+
+    #[test]
+    fn test_unclosed_arrays() {
+        let errors = errors("[1, 2", Parser::parse);
+        assert_eq!(
+            errors.last().unwrap(),
+            &("[", "unclosed delimiter".to_string())
+        );
+    }
+
+    #[test]
+    fn test_tuples() {
+        let unit = variant!(parse("()"), Expression::Literal);
+        variant!(unit, LiteralExpression::Unit);
+        variant!(parse("(a)"), Expression::Identifier);
+        assert_eq!(variant!(parse("(a,)"), Expression::Tuple).elements.len(), 1);
+        assert_eq!(
+            variant!(parse("(a, b)"), Expression::Tuple).elements.len(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_missing_tuple_commas() {
+        assert_eq!(
+            errors("(a, b c)", Parser::parse),
+            [("b c", "expected a `,` between these elements".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_lambdas() {
+        let source = "|x, y: Int| x + y";
+        let lambda = variant!(parse(source), Expression::Lambda);
+        assert_eq!(lambda.parameters.len(), 2);
+        assert!(lambda.parameters[0].annotation.is_none());
+        assert!(lambda.parameters[1].annotation.is_some());
+        assert_eq!(&source[lambda.body.span()], "x + y");
+
+        assert!(variant!(parse("|| 1"), Expression::Lambda)
+            .parameters
+            .is_empty());
+
+        let lambda = variant!(parse("|x| -> Int { x }"), Expression::Lambda);
+        assert!(lambda.return_type.is_some());
+        variant!(*lambda.body, Expression::Block);
+    }
+
+    #[test]
+    fn test_lambda_arguments() {
+        let source = "f(|x| x + 1, n: 2)";
+        let call = variant!(parse(source), Expression::Call);
+        assert_eq!(call.arguments.len(), 2);
+        let lambda = variant!(&call.arguments[0], Argument::Positional);
+        assert_eq!(&source[lambda.span()], "|x| x + 1");
+    }
+
+    #[test]
+    fn test_lambda_return_types_need_blocks() {
+        let errors = errors("|x| -> Int x", Parser::parse);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].0, "x");
+    }
+
+    #[test]
+    fn test_loops() {
+        let source = "'outer: while a { for x in xs { if x { break 'outer 1; } continue; } }";
+        let outer = variant!(parse(source), Expression::While);
+        assert_eq!(&source[outer.label.unwrap().span()], "'outer");
+
+        let body = variant!(*outer.body, Expression::Block);
+        let inner = variant!(*body.tail.unwrap(), Expression::For);
+        variant!(inner.pattern, Pattern::Word);
+        assert_eq!(&source[inner.iterable.span()], "xs");
+
+        let source = "loop { return 1 }";
+        let expression = variant!(parse(source), Expression::Loop);
+        let body = variant!(*expression.body, Expression::Block);
+        let ret = variant!(*body.tail.unwrap(), Expression::Return);
+        assert!(ret.value.is_some());
+    }
+
+    #[test]
+    fn test_jumps_outside_of_loops() {
+        assert_eq!(
+            errors("break", Parser::parse),
+            [(
+                "break",
+                "`break` can only be used inside of a loop".to_string()
+            )]
+        );
+        assert_eq!(
+            errors("continue", Parser::parse),
+            [(
+                "continue",
+                "`continue` can only be used inside of a loop".to_string()
+            )]
+        );
+        assert_eq!(
+            errors("loop { || { break } }", Parser::parse),
+            [(
+                "break",
+                "`break` can only be used inside of a loop".to_string()
+            )]
+        );
+        assert_eq!(
+            errors("loop { break 'nope }", Parser::parse),
+            [(
+                "break 'nope",
+                "there is no enclosing loop labelled `'nope`".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_ranges() {
+        let source = "0..len - 1";
+        let range = variant!(parse(source), Expression::Range);
+        assert!(!range.inclusive);
+        assert_eq!(&source[range.end.unwrap().span()], "len - 1");
+
+        let range = variant!(parse("..=b"), Expression::Range);
+        assert!(range.start.is_none() && range.inclusive);
+        let range = variant!(parse("a.."), Expression::Range);
+        assert!(range.end.is_none());
+
+        let expression = variant!(parse("for i in 0.. { }"), Expression::For);
+        variant!(*expression.iterable, Expression::Range);
+    }
+
+    #[test]
+    fn test_inclusive_ranges_need_an_end() {
+        assert_eq!(
+            errors("a..=", Parser::parse),
+            [("..=", "an inclusive range needs an end".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_chained_ranges() {
+        let message = "ranges can't be chained without parentheses".to_string();
+        assert_eq!(
+            errors("a..b..c", Parser::parse),
+            [("a..b..c", message.clone())]
+        );
+        assert_eq!(
+            errors("a.. ..c", Parser::parse),
+            [("a.. ..c", message.clone())]
+        );
+        assert_eq!(
+            errors("..b..c", Parser::parse),
+            [("..b..c", message.clone())]
+        );
+
+        // However long a chain is, it's only reported once.
+        assert_eq!(
+            errors("a..b..c..d", Parser::parse),
+            [("a..b..c..d", message)]
+        );
+
+        parse("(a..b)..c");
+        parse("a..(b..c)");
+    }
+
+    #[test]
+    fn test_casts() {
+        let source = "-x as Int";
+        let cast = variant!(parse(source), Expression::Cast);
+        variant!(*cast.expression, Expression::Unary);
+        assert_eq!(&source[cast.target.span()], "Int");
+
+        let source = "a * b as Float";
+        let product = variant!(parse(source), Expression::Binary);
+        variant!(*product.right, Expression::Cast);
+    }
+
+    #[test]
+    fn test_ascriptions() {
+        let source = "x: Map[K, List<V>] == y";
+        let comparison = variant!(parse(source), Expression::Binary);
+        let ascription = variant!(*comparison.left, Expression::Ascription);
+        assert_eq!(&source[ascription.annotation.span()], "Map[K, List<V>]");
+    }
+
+    #[test]
+    fn test_casts_before_comparisons() {
+        let source = "x as Int < y";
+        let comparison = variant!(parse(source), Expression::Binary);
+        assert!(matches!(
+            comparison.operator.operator(),
+            BinaryOperator::LessThan
+        ));
+        let cast = variant!(*comparison.left, Expression::Cast);
+        variant!(cast.target, TypeExpr::Path);
+
+        let source = "x as List[Int] < y";
+        let comparison = variant!(parse(source), Expression::Binary);
+        let cast = variant!(*comparison.left, Expression::Cast);
+        variant!(cast.target, TypeExpr::Generic);
+    }
+
+    #[test]
+    fn test_pipelines() {
+        let source = "0..n |> sum";
+        let pipeline = variant!(parse(source), Expression::Pipeline);
+        variant!(*pipeline.input, Expression::Range);
+
+        let source = "a = x |> f";
+        let assign = variant!(parse(source), Expression::Assign);
+        variant!(*assign.value, Expression::Pipeline);
+    }
+
+    #[test]
+    fn test_pipeline_desugaring() {
+        let source = "x |> f(y) |> g";
+        let pipeline = variant!(parse(source), Expression::Pipeline);
+        let outer = variant!(pipeline.desugar(), Expression::Call);
+        assert_eq!(&source[outer.operand.span()], "g");
+        assert_eq!(outer.arguments.len(), 1);
+
+        let inner = variant!(&outer.arguments[0], Argument::Positional);
+        let inner = variant!(inner, Expression::Call);
+        assert_eq!(&source[inner.operand.span()], "f");
+        let arguments: Vec<_> = inner
+            .arguments
+            .iter()
+            .map(|argument| &source[variant!(argument, Argument::Positional).span()])
+            .collect();
+        assert_eq!(arguments, ["x", "y"]);
+    }
+
+    #[test]
+    fn test_interpolated_strings() {
+        let source = r#""hello {name}!""#;
+        let string = variant!(parse(source), Expression::InterpolatedString);
+        assert_eq!(string.parts.len(), 3);
+        assert_eq!(
+            &source[*variant!(&string.parts[0], StringPart::Literal)],
+            "hello "
+        );
+        assert_eq!(
+            &source[variant!(&string.parts[1], StringPart::Expression).span()],
+            "name"
+        );
+        assert_eq!(
+            &source[*variant!(&string.parts[2], StringPart::Literal)],
+            "!"
+        );
+
+        let source = r#""a {{b}} {f("c")}""#;
+        let string = variant!(parse(source), Expression::InterpolatedString);
+        assert_eq!(
+            &source[*variant!(&string.parts[0], StringPart::Literal)],
+            "a {{b}} "
+        );
+        variant!(&string.parts[1], StringPart::Expression);
+
+        let literal = variant!(parse(r#""plain""#), Expression::Literal);
+        variant!(literal, LiteralExpression::String);
+    }
+
+    #[test]
+    fn test_empty_interpolations() {
+        assert_eq!(
+            errors(r#""{}""#, Parser::parse),
+            [("{}", "expected an expression inside of `{}`".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_unclosed_interpolations() {
+        let message = "unclosed `{` in string interpolation".to_string();
+        assert_eq!(
+            errors(r#"fn f() { "a {b" } fn g() {}"#, Parser::parse_module),
+            [("{", message.clone())]
+        );
+        assert_eq!(
+            errors("fn f() { \"a {b\nc\" }", Parser::parse_module),
+            [("{", message)]
+        );
+    }
+
+    #[test]
+    fn test_unterminated_strings() {
+        assert_eq!(
+            errors("fn f() {}\n\"abc {x}\nfn g() {}", Parser::parse_module),
+            [("\"", "unterminated string".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_doc_comments() {
+        let source = "//! Module docs.\n/// Item docs.\nfn f() {}";
+        let module = parse_module(source);
+        assert_eq!(module.attributes[0].style, AttributeStyle::Inner);
+        let text = *variant!(&module.attributes[0].kind, AttributeKind::Doc);
+        assert_eq!(&source[text], " Module docs.");
+
+        let function = variant!(&module.items[0], Item::Function);
+        assert_eq!(function.attributes[0].style, AttributeStyle::Outer);
+        let text = *variant!(&function.attributes[0].kind, AttributeKind::Doc);
+        assert_eq!(&source[text], " Item docs.");
+    }
+
+    #[test]
+    fn test_block_comments() {
+        let literal = variant!(parse("/* a /* b */ c */ 1"), Expression::Literal);
+        variant!(literal, LiteralExpression::Integer);
+    }
+
+    #[test]
+    fn test_unterminated_block_comments() {
+        let message = "unterminated block comment".to_string();
+        assert_eq!(
+            errors("/* unterminated\nfn f() {}", Parser::parse_module),
+            [("/*", message.clone())]
+        );
+        assert_eq!(
+            errors("fn f() {} /* a /* b */ fn g() {}", Parser::parse_module),
+            [("/*", message.clone())]
+        );
+        assert_eq!(
+            errors("fn f() {} /*", Parser::parse_module),
+            [("/*", message.clone())]
+        );
+        assert_eq!(
+            errors("1 + 2 /* unterminated", Parser::parse),
+            [("/*", message)]
+        );
+    }
 }
@@ -12,7 +12,7 @@ pub trait Spanned {
 /// # Why is this not Range<usize>?
 ///
 /// For some inane reason, `Range<usize>` is not `Copy`. This type is.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Span {
     start: usize,
     end: usize,
@@ -5,16 +5,29 @@ pub struct Precedence(u32);
 
 impl Precedence {
     pub const START: Self = Precedence(0);
-    pub const CONDITIONAL: Self = Precedence(1);
-    pub const IS: Self = Precedence(2);
-    pub const OR: Self = Precedence(3);
-    pub const AND: Self = Precedence(4);
-    pub const COMPARISON: Self = Precedence(5);
-    pub const SUM: Self = Precedence(6);
-    pub const PRODUCT: Self = Precedence(7);
-    pub const PREFIX: Self = Precedence(8);
-    pub const POSTFIX: Self = Precedence(9);
-    pub const CALL: Self = Precedence(10);
+    // Assignment binds looser than anything else, including the conditions of `if` and `match`, so that something like
+    // `if x = 1 { }` is rejected rather than silently assigning.
+    pub const ASSIGN: Self = Precedence(1);
+    pub const CONDITIONAL: Self = Precedence(2);
+    // Pipelines are the loosest binary operator, so that `0..n |> sum` pipes the whole range.
+    pub const PIPELINE: Self = Precedence(3);
+    // Ranges bind looser than every other binary operator except pipelines, so `0..len - 1` is `0..(len - 1)`. They're
+    // still above `CONDITIONAL`, so that `for i in 0..10 { }` works.
+    pub const RANGE: Self = Precedence(4);
+    pub const OR: Self = Precedence(5);
+    pub const AND: Self = Precedence(6);
+    // `is` binds tighter than `and`/`or`, so that `a and b is P` is `a and (b is P)`, matching only `b` against the
+    // pattern rather than `a and b`.
+    pub const IS: Self = Precedence(7);
+    pub const COMPARISON: Self = Precedence(8);
+    pub const SUM: Self = Precedence(9);
+    pub const PRODUCT: Self = Precedence(10);
+    // Casts and ascriptions bind tighter than any binary operator, but looser than prefix operators, so `-x as Int`
+    // casts `-x`, and `a * b as Int` only casts `b`.
+    pub const CAST: Self = Precedence(11);
+    pub const PREFIX: Self = Precedence(12);
+    pub const POSTFIX: Self = Precedence(13);
+    pub const CALL: Self = Precedence(14);
 
     pub fn up(self: Precedence) -> Self {
         Precedence(self.0 + 1)
@@ -43,21 +56,30 @@ pub trait TokenInfoExt {
 }
 
 impl TokenInfoExt for Token {
+    // This is the precedence used to decide whether an operator continues an expression. The right-hand side of a binary
+    // operator is parsed with `precedence().with(associativity())` instead, so that right associative operators can
+    // continue into their own right-hand side.
     fn precedence(&self) -> Precedence {
-        let precedence = match self {
+        match self {
+            Token::Equals | token_category![CompoundOperator] => Precedence::ASSIGN,
+            Token::PipeGreater => Precedence::PIPELINE,
+            Token::DotDot | Token::DotDotEquals => Precedence::RANGE,
+            Token::Is => Precedence::IS,
             Token::Or => Precedence::OR,
             Token::And => Precedence::AND,
             token_category![ComparisonOperator] => Precedence::COMPARISON,
             token_category![SumOperator] => Precedence::SUM,
             token_category![ProductOperator] => Precedence::PRODUCT,
+            Token::As | Token::Colon => Precedence::CAST,
+            Token::OpeningBracket => Precedence::POSTFIX,
+            Token::OpeningParen | Token::OpeningBrace | token_category![Path] => Precedence::CALL,
             _ => Precedence::START,
-        };
-
-        precedence.with(self.associativity())
+        }
     }
 
     fn associativity(&self) -> Associativity {
         match self {
+            Token::Equals | token_category![CompoundOperator] => Associativity::Right,
             _ => Associativity::Left,
         }
     }
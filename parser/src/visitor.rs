@@ -15,13 +15,16 @@ pub trait Visitor<Node> {
     }
 }
 
-impl<Node> Visitor<Node> for Vec<Node> {
+impl<Node, T> Visitor<Node> for Vec<T>
+where
+    T: Visitor<Node>,
+{
     fn visit<F>(&self, op: &mut F)
     where
         F: FnMut(&Node),
     {
-        for node in self.iter() {
-            op(node)
+        for visitor in self.iter() {
+            visitor.visit(op);
         }
     }
 }